@@ -0,0 +1 @@
+v nl {str}
@@ -0,0 +1 @@
+g ['|', '-', 'L', 'J', '7', 'F', '.', 'S']
@@ -0,0 +1 @@
+g c
@@ -0,0 +1 @@
+v nl {t sp {v ['.', '#', '?']} {v , usize}}
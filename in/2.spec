@@ -0,0 +1 @@
+v nl {tpl "Game " {id: u32} ": " {draws: v "; " {v ", " {t sp u32 [Color: "red", "green", "blue"]}}}}
@@ -0,0 +1 @@
+v nl {t ~:~ ** {t ~|~ {set sp+ u32} {v sp+ u32}}}
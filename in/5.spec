@@ -0,0 +1,4 @@
+sec ": " {v sp i}
+nlnl
+sec " map:\n" {v nl {t sp i {rl sp i}}}
+...
@@ -0,0 +1 @@
+v nl {t ~:~ ** {v sp+ u64}}
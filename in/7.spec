@@ -0,0 +1 @@
+v nl {t sp {str5} u64}
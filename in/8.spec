@@ -0,0 +1,3 @@
+v c
+nlnl
+hm nl " = " {str} {tpl "(" {left} ", " {right} ")"}
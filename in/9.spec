@@ -0,0 +1 @@
+v nl {v sp f}
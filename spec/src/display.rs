@@ -0,0 +1,197 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{
+    ContainerType, Delimiter, InnerType, IntType, Specification, SpecificationList, Table,
+    TableKey, TemplatePart, Wildcard,
+};
+
+// Everything is printed in spec syntax, so that the output parses back to the same value
+
+impl Display for Delimiter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Delimiter::None => write!(f, "<>"),
+            Delimiter::NewLine => write!(f, "nl"),
+            Delimiter::DoubleNewLine => write!(f, "nlnl"),
+            Delimiter::Space => write!(f, "sp"),
+            Delimiter::Custom(c) => write!(f, "{c:?}"),
+            Delimiter::Padded(delimiter) => write!(f, "~{delimiter}~"),
+            Delimiter::Repeated(delimiter) => write!(f, "{delimiter}+"),
+        }
+    }
+}
+
+impl Display for Wildcard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Wildcard::Char => write!(f, "*"),
+            Wildcard::Chars => write!(f, "**"),
+            Wildcard::Line => write!(f, "***"),
+        }
+    }
+}
+
+impl Display for TableKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            TableKey::Char(c) => write!(f, "{c:?}"),
+            TableKey::Str(s) => write!(f, "{s:?}"),
+        }
+    }
+}
+
+/// Named tables map to variants, other tables print their values
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let entries = self.entries.iter().map(|(key, value)| match self.name {
+            Some(_) => key.to_string(),
+            None => format!("{key} = {value}"),
+        });
+        let entries = entries.collect::<Vec<_>>().join(", ");
+        match &self.name {
+            Some(name) if entries.is_empty() => write!(f, "[{name}]"),
+            Some(name) => write!(f, "[{name}: {entries}]"),
+            None => write!(f, "[{entries}]"),
+        }
+    }
+}
+
+impl Display for InnerType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            InnerType::Integer(IntType::I64) => write!(f, "i"),
+            InnerType::Integer(ty) => write!(f, "{}", ty.name()),
+            InnerType::Float => write!(f, "f"),
+            InnerType::Char => write!(f, "c"),
+            InnerType::Nested(spec) => write!(f, "{{{spec}}}"),
+            InnerType::Wildcard(wildcard) => write!(f, "{wildcard}"),
+            InnerType::Table(table) => write!(f, "{table}"),
+        }
+    }
+}
+
+impl Display for Specification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let (container, inner_types) = match &self.container {
+            ContainerType::String => return write!(f, "str"),
+            ContainerType::FixedString(len) => return write!(f, "str{len}"),
+            ContainerType::Template(parts) => {
+                write!(f, "tpl")?;
+                for part in parts {
+                    match part {
+                        TemplatePart::Literal(text) => write!(f, " {text:?}")?,
+                        TemplatePart::Placeholder(name, InnerType::Nested(spec)) => {
+                            write!(f, " {{{name}: {spec}}}")?
+                        }
+                        TemplatePart::Placeholder(name, inner) => {
+                            write!(f, " {{{name}: {inner}}}")?
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            ContainerType::Range(kind, ty) => {
+                let ty = InnerType::Integer(*ty);
+                return write!(f, "{} {} {ty}", kind.name(), self.delimiter);
+            }
+            ContainerType::Chunks(len, inner) => {
+                write!(f, "ch {len}")?;
+                if self.delimiter != Delimiter::None {
+                    write!(f, " {}", self.delimiter)?;
+                }
+                return write!(f, " {inner}");
+            }
+            ContainerType::Section(inner) => ("sec", vec![inner]),
+            ContainerType::Vec(inner) => ("v", vec![inner]),
+            ContainerType::Array(len, inner) => {
+                write!(f, "v{len}")?;
+                if self.delimiter != Delimiter::None {
+                    write!(f, " {}", self.delimiter)?;
+                }
+                return write!(f, " {inner}");
+            }
+            ContainerType::Tuple(inners) => ("t", inners.iter().collect()),
+            // The separator follows the delimiter, which has to be written out then
+            ContainerType::HashMap(separator, key, value)
+            | ContainerType::MultiMap(separator, key, value) => {
+                let map = match self.container {
+                    ContainerType::HashMap(..) => "hm",
+                    _ => "mm",
+                };
+                return match separator {
+                    Delimiter::Space => write!(f, "{map} {} {key} {value}", self.delimiter),
+                    _ => write!(f, "{map} {} {separator} {key} {value}", self.delimiter),
+                };
+            }
+            ContainerType::HashSet(inner) => ("set", vec![inner]),
+            ContainerType::Grid(inner) => ("g", vec![inner]),
+        };
+        write!(f, "{container}")?;
+        if self.delimiter != Delimiter::None {
+            write!(f, " {}", self.delimiter)?;
+        }
+        for inner in inner_types {
+            write!(f, " {inner}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for SpecificationList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.specifications[0])?;
+        for (delimiter, spec) in self.macro_delimiters.iter().zip(&self.specifications[1..]) {
+            write!(f, "\n{}\n{spec}", macro_delimiter(delimiter))?;
+        }
+        if self.repeat_last {
+            write!(f, "\n...")?;
+        }
+        Ok(())
+    }
+}
+
+/// A macro delimiter line, whose text isn't quoted
+fn macro_delimiter(delimiter: &Delimiter) -> String {
+    match delimiter {
+        Delimiter::Custom(c) => escape(c),
+        Delimiter::Padded(delimiter) => format!("~{}~", macro_delimiter(delimiter)),
+        Delimiter::Repeated(delimiter) => format!("{}+", macro_delimiter(delimiter)),
+        _ => delimiter.to_string(),
+    }
+}
+
+/// Inverse of `unescape` for a macro delimiter line, which is taken verbatim otherwise
+pub fn escape(delimiter: &str) -> String {
+    let mut escaped = String::new();
+    for c in delimiter.chars() {
+        match c {
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            '\t' => escaped += "\\t",
+            '\r' => escaped += "\\r",
+            _ => escaped.push(c),
+        }
+    }
+    // Reserved words and surrounding whitespace would get lost otherwise
+    if ["", "<>", "nl", "nlnl", "sp", "..."].contains(&escaped.as_str()) {
+        let mut chars = escaped.chars();
+        let first = chars
+            .next()
+            .map_or(String::new(), |c| format!("\\u{{{:x}}}", u32::from(c)));
+        return first + chars.as_str();
+    }
+    // A literal would be taken for a padded or repeated delimiter otherwise
+    if escaped.len() > 2 && escaped.starts_with('~') && escaped.ends_with('~') {
+        escaped = format!("\\u{{7e}}{}", &escaped[1..]);
+    }
+    if escaped.len() > 1 && escaped.ends_with('+') {
+        escaped = format!("{}\\u{{2b}}", &escaped[..escaped.len() - 1]);
+    }
+    if let Some(rest) = escaped.strip_prefix(' ') {
+        escaped = format!("\\s{rest}");
+    }
+    if let Some(rest) = escaped.strip_suffix(' ') {
+        escaped = format!("{rest}\\s");
+    }
+    escaped
+}
@@ -0,0 +1,98 @@
+use std::fmt::Display;
+
+use proc_macro2::{Span, TokenStream};
+
+/// Error in a spec string, pointing at the offending part of the spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    pub message: String,
+    /// Line within the spec, starting at 1
+    pub line: usize,
+    /// Column within the line in chars, starting at 0
+    pub column: usize,
+    /// Number of chars the error spans, at least 1
+    pub len: usize,
+    /// Text of the offending spec line
+    pub source_line: String,
+}
+
+/// Whether tokens parsed from a string know where they are in it. Within a proc macro they
+/// don't, all of them have the span of the macro call, so errors are located at the whole
+/// spec line there.
+fn has_locations() -> bool {
+    "x".parse::<TokenStream>()
+        .ok()
+        .and_then(|tokens| tokens.into_iter().next())
+        .is_some_and(|token| token.span().start().line > 0)
+}
+
+impl SpecError {
+    pub(crate) fn new(message: impl Into<String>, column: usize, len: usize) -> Self {
+        SpecError {
+            message: message.into(),
+            line: 1,
+            column,
+            len: len.max(1),
+            source_line: String::new(),
+        }
+    }
+
+    /// Locates an error with the span of a token of `source_line`. `columns` maps the
+    /// column of every char that was tokenized to its column in `source_line`.
+    pub(crate) fn from_span(
+        message: impl Into<String>,
+        span: Span,
+        source_line: &str,
+        columns: &[usize],
+    ) -> Self {
+        let (start, end) = (span.start(), span.end());
+        let len = source_line.chars().count();
+        let (column, end_column) = if !has_locations() {
+            (0, len)
+        } else if start == end {
+            // Only the call site span is empty, it's used for the end of the line
+            (len, len + 1)
+        } else {
+            let column = columns[start.column.min(columns.len() - 1)];
+            let end_column = if end.line == start.line {
+                columns[end.column.min(columns.len() - 1)]
+            } else {
+                column + 1
+            };
+            (column, end_column)
+        };
+        SpecError::new(message, column, end_column.saturating_sub(column)).with_source(source_line)
+    }
+
+    pub(crate) fn with_source(mut self, source_line: &str) -> Self {
+        self.source_line = source_line.into();
+        self
+    }
+
+    pub(crate) fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    /// The location and the spec line with the error underlined, as printed by rustc
+    pub fn snippet(&self) -> String {
+        let line = self.line.to_string();
+        let pad = " ".repeat(line.len());
+        format!(
+            "{pad}--> spec:{}:{}\n{pad} |\n{line} | {}\n{pad} | {}{}",
+            self.line,
+            self.column + 1,
+            self.source_line,
+            " ".repeat(self.column),
+            "^".repeat(self.len)
+        )
+    }
+}
+
+impl Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: {}\n{}", self.message, self.snippet())
+    }
+}
+
+impl std::error::Error for SpecError {}
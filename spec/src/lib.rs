@@ -0,0 +1,1707 @@
+// ~separator~ == separator with any number of spaces or tabs left/right
+// separator+ == one or more separators in a row
+// <> == No separator
+// * == one any char
+// ** == many any chars except newline
+// *** == ignore line
+
+// Escape sequence start with \
+//   \n newline, \t tab, \r carriage return, \s space, \\ backslash, \u{2192} unicode char,
+//   \{ \, \" ... any ASCII punctuation stands for itself
+// A delimiter containing escape sequences ends at the next whitespace or unescaped brace:
+//   t \s|\s i i    3 | 4
+//   v \{ c        a{b{c
+// Inside string literals the escape sequences of Rust apply.
+// The reserved words can be used as literal delimiters with a string literal: v "nl" i
+
+/*
+Grammar of a single specification line:
+
+spec      := "str" integer? | container delimiter? inner+ | map delimiter separator? inner inner
+           | "tpl" part+ | "sec" delimiter inner
+container := "v" integer? | "t" | "set" | "g" | "r" | "ri" | "rl" | "ch" integer
+map       := "hm" | "mm"
+separator := delimiter
+delimiter := "<>" | "~" simple "~" | simple
+simple    := ("nl" | "nlnl" | "sp" | "\"literal\"" | ident | punct+) "+"?
+inner     := int | "f" | "c" | "{" spec "}" | "*" | "**" | "***" | table
+int       := "i" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize"
+           | "u8" | "u16" | "u32" | "u64" | "u128" | "usize"
+table     := "[" (name ":")? entry ("," entry)* ","? "]" | "[" name "]"
+entry     := ('char' | "string") ("=" integer)?
+part      := "\"literal\"" | "{" name (":" (inner | spec))? "}"
+
+The delimiter may be omitted (== <>) if the next token already is an inner type.
+"v", "set" and "g" take one inner type, "hm" a key and a value type, "t" one or more.
+
+v nl {v c}               ab\ncd              as [['a', 'b'], ['c', 'd']]
+
+"hm" splits its entries at the delimiter and each entry into key and value at the separator,
+which is a delimiter that defaults to "sp". A key may only occur once, "mm" is a multimap
+that collects the values of each key into a list instead:
+
+hm nl " = " {str} {tpl "(" {left} ", " {right} ")"}     AAA = (BBB, CCC)
+mm ~,~ ~->~ c i                                          a -> 1, b -> 2, a -> 3
+
+"i" is short for "i64". Integers that don't fit their type are reported as errors.
+
+A delimiter between "~" matches with any amount of spaces and tabs around it, one followed
+by "+" matches runs of itself. Runs of a repeated delimiter at the start and end of the input
+of a container are ignored, so right-aligned numbers split like with `split_whitespace`:
+
+t ~:~ ** {t ~|~ {v sp+ u32} {v sp+ u32}}      Card  1: 41 48  3 |  9 83 86
+v ~,~ i                                       1 ,2,  3
+
+"g" is a rectangular grid with one row per line, its delimiter separates the cells of a row.
+
+"r", "ri" and "rl" are ranges of their integer type, the delimiter separates the two numbers:
+start and exclusive end, start and inclusive end, or start and length. "ch n" groups the
+elements split at its delimiter into chunks of n, each chunk is the text from its first to
+its last element, which is parsed as the inner type.
+
+r ".." i                 3..7                as 3..7
+ri - u32                 2-4                 as 2..=4
+t sp i {rl sp i}         50 98 2             as (50, 98..100)
+ch 2 sp {rl sp i}        79 14 55 13         as [79..93, 55..68]
+
+A table is an alternation of chars or strings mapped to integers, entries without a value
+map to their index. A table named by an enum is typed as that enum in `parse_input!`, its
+alternatives are the tokens of the variants and can't have values. They can be left out,
+`[Color]` takes them from the enum, alternatives that are written have to be its tokens.
+
+"tpl" is a template of literal text and named placeholders, which are parsed as record
+fields. A placeholder without a type is a "str", a placeholder ends at the literal text
+following it, so there has to be literal text between two placeholders.
+
+tpl "Game " {id: u32} ": " {draws: v "; " {v ", " {t sp u32 {str}}}}
+tpl {from} " = (" {left} ", " {right} ")"       AAA = (BBB, CCC)
+
+g c                      #..#\n.##.
+g ['.', '#']             #..#\n.##.  as 1 0 0 1 ...
+v ['L' = -1, 'R' = 1]    LRRL
+v sp [Color: "red", "green", "blue"]    red blue
+v sp [Color]                            red blue
+
+Wildcards are only allowed as tuple fields. They match their part of the input and are
+left out of the parsed tuple; a tuple with a single remaining field is that field.
+"***" ignores the rest of the line including the line break, so no tuple delimiter follows.
+
+t ": " ** {v sp i}       Card 1: 41 48 83
+t nl *** {v nl {v sp i}}  seed-to-soil map:\n50 98 2\n52 50 48
+
+"v" and "str" followed by a number have a fixed length, without a space in between. They are
+typed as arrays in `parse_input!`, a different number of elements or chars is an error:
+
+v3 sp i                  50 98 2             as [50, 98, 2]
+t sp {str5} u32          32T3K 765           as (['3', '2', 'T', '3', 'K'], 765)
+
+"sec" is a named section: the text before the first delimiter is its title, the rest is
+parsed as the inner type. Sections of a parsed input can be looked up by title.
+
+sec " map:\n" {v nl {v sp i}}     seed-to-soil map:\n50 98 2\n52 50 48
+
+A specification list consists of one specification line, optionally followed by
+pairs of (macro delimiter line, specification line). A last line "..." repeats the last
+specification until the end of the input, separated by the last macro delimiter, its
+sections are collected in a list:
+
+sec ": " {v sp i}
+nlnl
+sec " map:\n" {v nl {v sp i}}
+...
+
+A macro delimiter line of three dots is written "\...".
+*/
+
+use std::{borrow::Cow, str::FromStr, sync::Arc};
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    *,
+};
+
+mod display;
+mod error;
+
+pub use display::escape;
+pub use error::SpecError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecificationList {
+    pub specifications: Vec<Specification>,
+    pub macro_delimiters: Vec<Delimiter>,
+    /// The last specification repeats until the end of the input, `...`
+    pub repeat_last: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Specification {
+    pub delimiter: Delimiter,
+    pub container: ContainerType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delimiter {
+    None,
+    NewLine,
+    DoubleNewLine,
+    Space,
+    Custom(Box<str>),
+    /// Any amount of spaces and tabs around the delimiter, `~|~`
+    Padded(Box<Delimiter>),
+    /// Runs of one or more delimiters, `sp+`
+    Repeated(Box<Delimiter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContainerType {
+    Vec(InnerType),
+    /// `Vec` with a fixed number of elements, `v3`
+    Array(usize, InnerType),
+    Tuple(Vec<InnerType>),
+    /// Key and value of an entry are split at the first separator, duplicate keys are errors
+    HashMap(Delimiter, InnerType, InnerType),
+    /// Like `HashMap`, but the values of each key are collected in their order
+    MultiMap(Delimiter, InnerType, InnerType),
+    HashSet(InnerType),
+    /// Rows are lines, the delimiter separates the cells of a row
+    Grid(InnerType),
+    Template(Vec<TemplatePart>),
+    /// Integer range, the delimiter separates its two numbers
+    Range(RangeKind, IntType),
+    /// Elements grouped into chunks of a fixed number, each chunk is parsed as the inner type
+    Chunks(usize, InnerType),
+    /// Title before the first delimiter, the rest is parsed as the inner type
+    Section(InnerType),
+    String,
+    /// `String` with a fixed number of chars, `str5`
+    FixedString(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeKind {
+    /// `r`: start and end, exclusive like `start..end`
+    Exclusive,
+    /// `ri`: start and end, inclusive like `start..=end`
+    Inclusive,
+    /// `rl`: start and length, `start..start + len`
+    Length,
+}
+
+impl RangeKind {
+    /// Name of the container in specs
+    pub fn name(self) -> &'static str {
+        match self {
+            RangeKind::Exclusive => "r",
+            RangeKind::Inclusive => "ri",
+            RangeKind::Length => "rl",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    Literal(Box<str>),
+    /// The name is shared with the records parsed by the template
+    Placeholder(Arc<str>, InnerType),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InnerType {
+    Integer(IntType),
+    Float,
+    Char,
+    Nested(Box<Specification>),
+    Wildcard(Wildcard),
+    /// Alternation of tokens mapped to integers
+    Table(Table),
+}
+
+/// Alternatives of a table mapped to integers. A named table is typed as the enum of that
+/// name by the `parse_input!` macro, its alternatives are the tokens of the variants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub name: Option<Box<str>>,
+    pub entries: Vec<(TableKey, i64)>,
+}
+
+/// Alternative of a table, written as a char or a string literal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableKey {
+    Char(char),
+    Str(Box<str>),
+}
+
+impl TableKey {
+    /// The input text of the alternative
+    pub fn text(&self) -> Cow<'_, str> {
+        match self {
+            TableKey::Char(c) => Cow::Owned(c.to_string()),
+            TableKey::Str(s) => Cow::Borrowed(s),
+        }
+    }
+}
+
+impl SpecificationList {
+    /// Tables of all specifications, including the ones of nested specs
+    pub fn tables_mut(&mut self) -> Vec<&mut Table> {
+        self.specifications
+            .iter_mut()
+            .flat_map(Specification::tables_mut)
+            .collect()
+    }
+}
+
+impl Specification {
+    /// Tables of the spec, including the ones of nested specs
+    pub fn tables_mut(&mut self) -> Vec<&mut Table> {
+        let inners: Vec<&mut InnerType> = match &mut self.container {
+            ContainerType::Vec(inner)
+            | ContainerType::Array(_, inner)
+            | ContainerType::HashSet(inner)
+            | ContainerType::Grid(inner)
+            | ContainerType::Chunks(_, inner)
+            | ContainerType::Section(inner) => vec![inner],
+            ContainerType::Tuple(inners) => inners.iter_mut().collect(),
+            ContainerType::HashMap(_, key, value) | ContainerType::MultiMap(_, key, value) => {
+                vec![key, value]
+            }
+            ContainerType::Template(parts) => parts
+                .iter_mut()
+                .filter_map(|part| match part {
+                    TemplatePart::Placeholder(_, inner) => Some(inner),
+                    TemplatePart::Literal(_) => None,
+                })
+                .collect(),
+            ContainerType::Range(..) | ContainerType::String | ContainerType::FixedString(_) => {
+                vec![]
+            }
+        };
+        inners
+            .into_iter()
+            .flat_map(|inner| match inner {
+                InnerType::Table(table) => vec![table],
+                InnerType::Nested(spec) => spec.tables_mut(),
+                _ => vec![],
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntType {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+}
+
+impl IntType {
+    pub const ALL: [IntType; 12] = [
+        IntType::I8,
+        IntType::I16,
+        IntType::I32,
+        IntType::I64,
+        IntType::I128,
+        IntType::Isize,
+        IntType::U8,
+        IntType::U16,
+        IntType::U32,
+        IntType::U64,
+        IntType::U128,
+        IntType::Usize,
+    ];
+
+    /// Name of the Rust type, which is also its name in specs
+    pub fn name(self) -> &'static str {
+        match self {
+            IntType::I8 => "i8",
+            IntType::I16 => "i16",
+            IntType::I32 => "i32",
+            IntType::I64 => "i64",
+            IntType::I128 => "i128",
+            IntType::Isize => "isize",
+            IntType::U8 => "u8",
+            IntType::U16 => "u16",
+            IntType::U32 => "u32",
+            IntType::U64 => "u64",
+            IntType::U128 => "u128",
+            IntType::Usize => "usize",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "i" => Some(IntType::I64),
+            _ => IntType::ALL.into_iter().find(|ty| ty.name() == name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wildcard {
+    /// `*`: one any char
+    Char,
+    /// `**`: many any chars except newline
+    Chars,
+    /// `***`: ignore line
+    Line,
+}
+
+impl Parse for Specification {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Specification::parse_nested(input, None)
+    }
+}
+
+/// Description of the next token for error messages, the end of a nested spec is its `}`
+fn found(input: ParseStream, end: Option<Span>) -> String {
+    match input.cursor().token_tree() {
+        Some((TokenTree::Group(group), _)) => match group.delimiter() {
+            proc_macro2::Delimiter::Brace => "`{`".into(),
+            proc_macro2::Delimiter::Parenthesis => "`(`".into(),
+            proc_macro2::Delimiter::Bracket => "`[`".into(),
+            proc_macro2::Delimiter::None => format!("`{group}`"),
+        },
+        Some((token, _)) => format!("`{token}`"),
+        None if end.is_some() => "`}`".into(),
+        None => "end of spec".into(),
+    }
+}
+
+/// Error at the next token. `end` is the span of the `}` closing a nested spec, the
+/// end of the spec line is located by the call site span.
+fn error_at_next(input: ParseStream, end: Option<Span>, message: String) -> Error {
+    if input.is_empty() {
+        Error::new(end.unwrap_or_else(Span::call_site), message)
+    } else {
+        input.error(message)
+    }
+}
+
+/// Names of the containers, which start a (nested) spec
+const CONTAINERS: [&str; 13] = [
+    "v", "t", "hm", "mm", "set", "g", "tpl", "r", "ri", "rl", "ch", "sec", "str",
+];
+
+/// Containers with a fixed length written after their name, `v3` and `str5`
+const FIXED_LENGTH: [&str; 2] = ["v", "str"];
+
+/// Name of a container and its fixed length, if the name is followed by digits
+fn split_length(ident: &str) -> (&str, Option<&str>) {
+    let name = ident.trim_end_matches(|c: char| c.is_ascii_digit());
+    if name.len() < ident.len() && FIXED_LENGTH.contains(&name) {
+        (name, Some(&ident[name.len()..]))
+    } else {
+        (ident, None)
+    }
+}
+
+impl Specification {
+    fn parse_nested(input: ParseStream, end: Option<Span>) -> Result<Self> {
+        let is_container = |ident: &Ident| {
+            let ident = ident.to_string();
+            CONTAINERS.contains(&split_length(&ident).0)
+        };
+        if !input
+            .cursor()
+            .ident()
+            .is_some_and(|(ident, _)| is_container(&ident))
+        {
+            return Err(error_at_next(
+                input,
+                end,
+                format!(
+                    "expected container (`v`, `t`, `hm`, `mm`, `set`, `g`, `tpl`, `r`, `ri`, \
+                     `rl`, `ch`, `sec` or `str`), found {}",
+                    found(input, end)
+                ),
+            ));
+        }
+        let container_ty: Ident = input.parse()?;
+        let ident = container_ty.to_string();
+        let (name, fixed_len) = split_length(&ident);
+        let fixed_len = match fixed_len.map(str::parse::<usize>) {
+            Some(Ok(0)) => {
+                return Err(Error::new(
+                    container_ty.span(),
+                    "fixed-length containers need at least one element",
+                ))
+            }
+            Some(Ok(len)) => Some(len),
+            Some(Err(e)) => return Err(Error::new(container_ty.span(), e)),
+            None => None,
+        };
+        if container_ty == "tpl" {
+            return Ok(Specification {
+                delimiter: Delimiter::None,
+                container: ContainerType::Template(TemplatePart::parse_all(input, end)?),
+            });
+        }
+
+        let chunk_len = if container_ty == "ch" {
+            let len = input.parse::<LitInt>()?;
+            match len.base10_parse::<usize>()? {
+                0 => return Err(Error::new(len.span(), "chunks need at least one element")),
+                len => Some(len),
+            }
+        } else {
+            None
+        };
+
+        let delimiter = if name == "str" || input.is_empty() || InnerType::peek(input) {
+            Delimiter::None
+        } else if Delimiter::peek(input) {
+            input.parse()?
+        } else {
+            return Err(error_at_next(
+                input,
+                end,
+                format!(
+                    "expected delimiter or inner type after `{container_ty}`, found {}",
+                    found(input, end)
+                ),
+            ));
+        };
+
+        let container = match name {
+            "str" => match fixed_len {
+                Some(len) => ContainerType::FixedString(len),
+                None => ContainerType::String,
+            },
+            "v" => {
+                let inner = InnerType::parse_element(input, &container_ty, end)?;
+                match fixed_len {
+                    Some(len) => ContainerType::Array(len, inner),
+                    None => ContainerType::Vec(inner),
+                }
+            }
+            "set" => ContainerType::HashSet(InnerType::parse_element(input, &container_ty, end)?),
+            "g" => ContainerType::Grid(InnerType::parse_element(input, &container_ty, end)?),
+            "hm" | "mm" => {
+                // The separator of key and value defaults to a space
+                let separator = if !input.is_empty() && !InnerType::peek(input) {
+                    let span = input.span();
+                    let separator = input.parse::<Delimiter>()?;
+                    if separator.text().is_none() {
+                        return Err(Error::new(span, "key and value need a separator"));
+                    }
+                    separator
+                } else {
+                    Delimiter::Space
+                };
+                let key = InnerType::parse_element(input, &container_ty, end)?;
+                let value = InnerType::parse_element(input, &container_ty, end)?;
+                if container_ty == "hm" {
+                    ContainerType::HashMap(separator, key, value)
+                } else {
+                    ContainerType::MultiMap(separator, key, value)
+                }
+            }
+            "sec" => {
+                if delimiter.text().is_none() {
+                    return Err(Error::new(
+                        container_ty.span(),
+                        "`sec` needs a delimiter after its title",
+                    ));
+                }
+                ContainerType::Section(InnerType::parse_element(input, &container_ty, end)?)
+            }
+            "ch" => ContainerType::Chunks(
+                chunk_len.unwrap(),
+                InnerType::parse_element(input, &container_ty, end)?,
+            ),
+            "r" | "ri" | "rl" => {
+                let kind = match container_ty.to_string().as_str() {
+                    "r" => RangeKind::Exclusive,
+                    "ri" => RangeKind::Inclusive,
+                    _ => RangeKind::Length,
+                };
+                if delimiter == Delimiter::None {
+                    return Err(Error::new(
+                        container_ty.span(),
+                        format!("`{container_ty}` needs a delimiter between its numbers"),
+                    ));
+                }
+                let span = input.span();
+                match InnerType::parse_element(input, &container_ty, end)? {
+                    InnerType::Integer(ty) => ContainerType::Range(kind, ty),
+                    inner => {
+                        return Err(Error::new(
+                            span,
+                            format!("ranges are of integers, found `{inner}`"),
+                        ))
+                    }
+                }
+            }
+            _ => {
+                let mut inner_types = vec![InnerType::parse_after(input, &container_ty, end)?];
+                while !input.is_empty() {
+                    inner_types.push(InnerType::parse_after(input, &container_ty, end)?);
+                }
+                if inner_types
+                    .iter()
+                    .all(|inner| matches!(inner, InnerType::Wildcard(_)))
+                {
+                    return Err(Error::new(
+                        container_ty.span(),
+                        "tuple needs at least one field that is not a wildcard",
+                    ));
+                }
+                ContainerType::Tuple(inner_types)
+            }
+        };
+        if !input.is_empty() {
+            return Err(error_at_next(
+                input,
+                end,
+                format!(
+                    "expected {} after `{container_ty}` spec, found {}",
+                    if end.is_some() { "`}`" } else { "end of spec" },
+                    found(input, end)
+                ),
+            ));
+        }
+        Ok(Specification {
+            delimiter,
+            container,
+        })
+    }
+}
+
+impl InnerType {
+    /// Whether the next token starts an inner type rather than a delimiter
+    fn peek(input: ParseStream) -> bool {
+        input.peek(token::Brace)
+            || input.peek(token::Bracket)
+            || input.peek(Token![*])
+            || input.cursor().ident().is_some_and(|(ident, _)| {
+                ["f", "c"].iter().any(|i| ident == i)
+                    || IntType::from_name(&ident.to_string()).is_some()
+            })
+    }
+
+    /// Inner type of `v`, `set` and `hm`, which can't be a wildcard
+    fn parse_element(input: ParseStream, container: &Ident, end: Option<Span>) -> Result<Self> {
+        let span = input.span();
+        match InnerType::parse_after(input, container, end)? {
+            InnerType::Wildcard(_) => Err(Error::new(
+                span,
+                format!("wildcards are only allowed as tuple fields, not in `{container}`"),
+            )),
+            inner => Ok(inner),
+        }
+    }
+
+    fn parse_after(input: ParseStream, container: &Ident, end: Option<Span>) -> Result<Self> {
+        if input.peek(Token![*]) {
+            return input.step(|cursor| {
+                let mut n_stars = 0;
+                let mut rest = *cursor;
+                while let Some((punct, next)) = rest.punct() {
+                    if punct.as_char() != '*' {
+                        break;
+                    }
+                    n_stars += 1;
+                    rest = next;
+                    if punct.spacing() == proc_macro2::Spacing::Alone {
+                        break;
+                    }
+                }
+                match n_stars {
+                    1 => Ok((InnerType::Wildcard(Wildcard::Char), rest)),
+                    2 => Ok((InnerType::Wildcard(Wildcard::Chars), rest)),
+                    3 => Ok((InnerType::Wildcard(Wildcard::Line), rest)),
+                    _ => Err(cursor.error(format!(
+                        "invalid wildcard `{}`, expected `*`, `**` or `***`",
+                        "*".repeat(n_stars)
+                    ))),
+                }
+            });
+        }
+        if input.peek(token::Brace) {
+            let content;
+            let brace = braced!(content in input);
+            return Ok(InnerType::Nested(Box::new(Specification::parse_nested(
+                &content,
+                Some(brace.span.close()),
+            )?)));
+        }
+        if input.peek(token::Bracket) {
+            return InnerType::parse_table(input);
+        }
+        let ident = input.cursor().ident().map(|(ident, _)| ident.to_string());
+        let inner = match ident.as_deref() {
+            Some("f") => Some(InnerType::Float),
+            Some("c") => Some(InnerType::Char),
+            name => name.and_then(IntType::from_name).map(InnerType::Integer),
+        };
+        let Some(inner) = inner else {
+            return Err(error_at_next(
+                input,
+                end,
+                format!(
+                    "expected inner type after `{container}`, found {}",
+                    found(input, end)
+                ),
+            ));
+        };
+        input.parse::<Ident>()?;
+        Ok(inner)
+    }
+}
+
+impl TemplatePart {
+    fn parse_all(input: ParseStream, end: Option<Span>) -> Result<Vec<Self>> {
+        let mut parts: Vec<TemplatePart> = vec![];
+        while !input.is_empty() {
+            let span = input.span();
+            let part = if input.peek(LitStr) {
+                let literal = input.parse::<LitStr>()?;
+                if literal.value().is_empty() {
+                    return Err(Error::new(span, "literal text can't be empty"));
+                }
+                TemplatePart::Literal(literal.value().into())
+            } else if input.peek(token::Brace) {
+                TemplatePart::parse_placeholder(input)?
+            } else {
+                return Err(error_at_next(
+                    input,
+                    end,
+                    format!(
+                        "expected literal text or placeholder in `tpl`, found {}",
+                        found(input, end)
+                    ),
+                ));
+            };
+            match (parts.last(), &part) {
+                (Some(TemplatePart::Placeholder(..)), TemplatePart::Placeholder(..)) => {
+                    return Err(Error::new(
+                        span,
+                        "placeholders need literal text between them",
+                    ))
+                }
+                (Some(TemplatePart::Literal(_)), TemplatePart::Literal(_)) => {
+                    return Err(Error::new(span, "literal texts have to be joined"))
+                }
+                _ => {}
+            }
+            if let TemplatePart::Placeholder(name, _) = &part {
+                if parts
+                    .iter()
+                    .any(|p| matches!(p, TemplatePart::Placeholder(other, _) if other == name))
+                {
+                    return Err(Error::new(span, format!("duplicate placeholder `{name}`")));
+                }
+            }
+            parts.push(part);
+        }
+        if !parts
+            .iter()
+            .any(|p| matches!(p, TemplatePart::Placeholder(..)))
+        {
+            return Err(Error::new(
+                end.unwrap_or_else(Span::call_site),
+                "template needs at least one placeholder",
+            ));
+        }
+        Ok(parts)
+    }
+
+    /// `{name}`, `{name: inner}` or `{name: spec}`
+    fn parse_placeholder(input: ParseStream) -> Result<Self> {
+        let content;
+        let brace = braced!(content in input);
+        let end = Some(brace.span.close());
+        if !content.peek(Ident::peek_any) {
+            return Err(error_at_next(
+                &content,
+                end,
+                format!("expected placeholder name, found {}", found(&content, end)),
+            ));
+        }
+        let name = content.call(Ident::parse_any)?;
+        if content.is_empty() {
+            return Ok(TemplatePart::Placeholder(
+                name.to_string().into(),
+                InnerType::Nested(Box::new(Specification {
+                    delimiter: Delimiter::None,
+                    container: ContainerType::String,
+                })),
+            ));
+        }
+        content.parse::<Token![:]>()?;
+        let is_spec = content
+            .cursor()
+            .ident()
+            .is_some_and(|(ident, _)| CONTAINERS.iter().any(|c| ident == c));
+        let inner = if is_spec {
+            InnerType::Nested(Box::new(Specification::parse_nested(&content, end)?))
+        } else {
+            let inner = InnerType::parse_element(&content, &name, end)?;
+            if !content.is_empty() {
+                return Err(error_at_next(
+                    &content,
+                    end,
+                    format!(
+                        "expected `}}` after placeholder `{name}`, found {}",
+                        found(&content, end)
+                    ),
+                ));
+            }
+            inner
+        };
+        Ok(TemplatePart::Placeholder(name.to_string().into(), inner))
+    }
+}
+
+impl InnerType {
+    fn parse_table(input: ParseStream) -> Result<Self> {
+        let content;
+        let bracket = bracketed!(content in input);
+        let name = if content.peek(Ident) {
+            let name = content.parse::<Ident>()?;
+            if !content.is_empty() {
+                content.parse::<Token![:]>()?;
+            }
+            Some(name)
+        } else {
+            None
+        };
+        let mut entries: Vec<(TableKey, i64)> = vec![];
+        while !content.is_empty() {
+            let (key, span) = if content.peek(LitChar) {
+                let c = content.parse::<LitChar>()?;
+                (TableKey::Char(c.value()), c.span())
+            } else if content.peek(LitStr) {
+                let s = content.parse::<LitStr>()?;
+                match s.value().chars().count() {
+                    0 => return Err(Error::new(s.span(), "empty string in table")),
+                    _ => (TableKey::Str(s.value().into()), s.span()),
+                }
+            } else {
+                return Err(content.error(format!(
+                    "expected char or string literal in table, found {}",
+                    found(&content, Some(bracket.span.close()))
+                )));
+            };
+            let value = if content.peek(Token![=]) {
+                let eq = content.parse::<Token![=]>()?;
+                if let Some(name) = &name {
+                    return Err(Error::new(
+                        eq.span,
+                        format!(
+                            "the alternatives of `{name}` map to its variants, they have no values"
+                        ),
+                    ));
+                }
+                let negative = content.parse::<Option<Token![-]>>()?.is_some();
+                let value = content.parse::<LitInt>()?.base10_parse::<i64>()?;
+                if negative {
+                    -value
+                } else {
+                    value
+                }
+            } else {
+                entries.len() as i64
+            };
+            if entries.iter().any(|(other, _)| other.text() == key.text()) {
+                return Err(Error::new(
+                    span,
+                    format!("duplicate alternative {key} in table"),
+                ));
+            }
+            entries.push((key, value));
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        // The alternatives of a named table can be taken from its enum
+        if entries.is_empty() && name.is_none() {
+            return Err(Error::new(
+                bracket.span.join(),
+                "table needs at least one entry",
+            ));
+        }
+        Ok(InnerType::Table(Table {
+            name: name.map(|name| name.to_string().into()),
+            entries,
+        }))
+    }
+}
+
+impl Delimiter {
+    fn peek(input: ParseStream) -> bool {
+        input.peek(LitStr) || input.peek(Ident::peek_any) || input.cursor().punct().is_some()
+    }
+
+    /// A `+` after a literal or word delimiter repeats it
+    fn parse_repeated(input: ParseStream, delimiter: Delimiter) -> Result<Self> {
+        if input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            Ok(Delimiter::Repeated(Box::new(delimiter)))
+        } else {
+            Ok(delimiter)
+        }
+    }
+}
+
+impl Parse for Delimiter {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![<]) && input.peek2(Token![>]) {
+            input.parse::<Token![<]>()?;
+            input.parse::<Token![>]>()?;
+            return Ok(Delimiter::None);
+        }
+        // Punctuation like `~|~` is padded by `FromStr`, literals and words like `~"; "~` here
+        if input.peek(Token![~]) && (input.peek2(LitStr) || input.peek2(Ident::peek_any)) {
+            input.parse::<Token![~]>()?;
+            let delimiter = input.parse()?;
+            input.parse::<Token![~]>()?;
+            return Ok(Delimiter::Padded(Box::new(delimiter)));
+        }
+        if input.peek(LitStr) {
+            let literal = Delimiter::Custom(input.parse::<LitStr>()?.value().into());
+            return Delimiter::parse_repeated(input, literal);
+        }
+        if input.peek(Ident::peek_any) {
+            let ident = input.call(Ident::parse_any)?;
+            let delimiter = ident
+                .to_string()
+                .parse()
+                .map_err(|e: SpecError| Error::new(ident.span(), e.message))?;
+            return Delimiter::parse_repeated(input, delimiter);
+        }
+        // Joint punctuation such as `->` forms a single delimiter
+        input.step(|cursor| {
+            let mut delimiter = String::new();
+            let mut rest = *cursor;
+            while let Some((punct, next)) = rest.punct() {
+                delimiter.push(punct.as_char());
+                rest = next;
+                if punct.spacing() == proc_macro2::Spacing::Alone {
+                    break;
+                }
+            }
+            if delimiter.is_empty() {
+                Err(cursor.error("expected delimiter"))
+            } else {
+                delimiter
+                    .parse()
+                    .map(|delimiter| (delimiter, rest))
+                    .map_err(|e: SpecError| cursor.error(e.message))
+            }
+        })
+    }
+}
+
+impl FromStr for Delimiter {
+    type Err = SpecError;
+
+    fn from_str(value: &str) -> std::result::Result<Self, SpecError> {
+        Ok(match value {
+            "" | "<>" => Delimiter::None,
+            "nl" => Delimiter::NewLine,
+            "nlnl" => Delimiter::DoubleNewLine,
+            "sp" => Delimiter::Space,
+            _ if value.len() > 2 && value.starts_with('~') && value.ends_with('~') => {
+                Delimiter::Padded(Box::new(flexible(value, &value[1..value.len() - 1])?))
+            }
+            _ if value.len() > 1 && value.ends_with('+') => {
+                Delimiter::Repeated(Box::new(flexible(value, &value[..value.len() - 1])?))
+            }
+            _ => Delimiter::Custom(unescape(value)?.into()),
+        })
+    }
+}
+
+/// The delimiter within a padded or repeated one, which needs some text
+fn flexible(value: &str, inner: &str) -> std::result::Result<Delimiter, SpecError> {
+    let delimiter = inner.parse::<Delimiter>()?;
+    if delimiter.text().is_none() {
+        return Err(SpecError::new(
+            format!("`{value}` pads or repeats no delimiter"),
+            0,
+            value.chars().count(),
+        )
+        .with_source(value));
+    }
+    Ok(delimiter)
+}
+
+/// Resolves the escape sequence starting with the `\` at `chars[start]`,
+/// returns the char and the index after the sequence
+fn unescape_at(chars: &[char], start: usize) -> std::result::Result<(char, usize), SpecError> {
+    let c = match chars.get(start + 1) {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some('r') => '\r',
+        Some('s') => ' ',
+        Some('u') => {
+            let end = chars[start..]
+                .iter()
+                .position(|&c| c == '}')
+                .map_or(chars.len(), |i| start + i + 1);
+            let sequence = chars[start..end].iter().collect::<String>();
+            return sequence
+                .strip_prefix("\\u{")
+                .and_then(|hex| hex.strip_suffix('}'))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32)
+                .map(|c| (c, end))
+                .ok_or_else(|| {
+                    SpecError::new(
+                        format!("invalid unicode escape `{sequence}`"),
+                        start,
+                        end - start,
+                    )
+                });
+        }
+        Some(&c) if c.is_ascii_punctuation() => c,
+        Some(c) => {
+            return Err(SpecError::new(
+                format!("unknown escape sequence `\\{c}`"),
+                start,
+                2,
+            ))
+        }
+        None => return Err(SpecError::new("incomplete escape sequence `\\`", start, 1)),
+    };
+    Ok((c, start + 2))
+}
+
+/// Resolves all escape sequences in a delimiter
+pub fn unescape(value: &str) -> std::result::Result<String, SpecError> {
+    let chars = value.chars().collect::<Vec<_>>();
+    let mut unescaped = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '\\' {
+            let (c, next) = unescape_at(&chars, i).map_err(|e| e.with_source(value))?;
+            unescaped.push(c);
+            i = next;
+        } else {
+            unescaped.push(chars[i]);
+            i += 1;
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Replaces delimiters containing escape sequences by the equivalent string literal,
+/// as a backslash can't be tokenized. Also returns the column in `line` of every char.
+fn quote_escaped_delimiters(line: &str) -> std::result::Result<(String, Vec<usize>), SpecError> {
+    let is_boundary = |c: char| c.is_whitespace() || ['{', '}', '"'].contains(&c);
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut quoted = vec![];
+    let mut columns = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => {
+                let mut end = i + 1;
+                while end < chars.len() {
+                    end += match chars[end] {
+                        '\\' => 2,
+                        '"' => {
+                            end += 1;
+                            break;
+                        }
+                        _ => 1,
+                    };
+                }
+                let end = end.min(chars.len());
+                quoted.extend(&chars[i..end]);
+                columns.extend(i..end);
+                i = end;
+            }
+            // Char literals of tables keep their escape sequences
+            '\'' if chars.get(i + 2) == Some(&'\'') || chars.get(i + 1) == Some(&'\\') => {
+                let mut end = if chars[i + 1] == '\\' { i + 3 } else { i + 2 };
+                while end < chars.len() && chars[end] != '\'' {
+                    end += 1;
+                }
+                let end = (end + 1).min(chars.len());
+                quoted.extend(&chars[i..end]);
+                columns.extend(i..end);
+                i = end;
+            }
+            '\\' => {
+                // The part of the delimiter in front of the first escape sequence
+                let mut start = quoted.len();
+                while start > 0 && !is_boundary(quoted[start - 1]) {
+                    start -= 1;
+                }
+                let column = columns.get(start).copied().unwrap_or(i);
+                let mut delimiter = quoted.drain(start..).collect::<String>();
+                columns.truncate(start);
+                while i < chars.len() && !is_boundary(chars[i]) {
+                    if chars[i] == '\\' {
+                        let (c, next) = unescape_at(&chars, i)?;
+                        delimiter.push(c);
+                        i = next;
+                    } else {
+                        delimiter.push(chars[i]);
+                        i += 1;
+                    }
+                }
+                let literal = format!("{delimiter:?}");
+                columns.extend(literal.chars().map(|_| column));
+                quoted.extend(literal.chars());
+            }
+            c => {
+                quoted.push(c);
+                columns.push(i);
+                i += 1;
+            }
+        }
+    }
+    columns.push(chars.len());
+    Ok((quoted.into_iter().collect(), columns))
+}
+
+impl FromStr for Specification {
+    type Err = SpecError;
+
+    fn from_str(spec: &str) -> std::result::Result<Self, SpecError> {
+        let (quoted, columns) = quote_escaped_delimiters(spec).map_err(|e| e.with_source(spec))?;
+        let tokens = quoted
+            .parse::<TokenStream>()
+            .map_err(|e| SpecError::from_span("invalid token", e.span(), spec, &columns))?;
+        syn::parse2(tokens)
+            .map_err(|e| SpecError::from_span(e.to_string(), e.span(), spec, &columns))
+    }
+}
+
+/// Spaces and tabs around a padded delimiter
+fn is_padding(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+impl Delimiter {
+    /// The literal text separating two elements, `None` if every char is an element. A padded
+    /// or repeated delimiter has the text of a single unpadded delimiter.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Delimiter::None => None,
+            Delimiter::NewLine => Some("\n"),
+            Delimiter::DoubleNewLine => Some("\n\n"),
+            Delimiter::Space => Some(" "),
+            Delimiter::Custom(c) => Some(c),
+            Delimiter::Padded(delimiter) | Delimiter::Repeated(delimiter) => delimiter.text(),
+        }
+    }
+
+    /// Whether the delimiter matches more than its text
+    pub fn is_flexible(&self) -> bool {
+        matches!(self, Delimiter::Padded(_) | Delimiter::Repeated(_))
+    }
+
+    /// Byte range of the first delimiter within the input
+    pub fn find(&self, input: &str) -> Option<std::ops::Range<usize>> {
+        match self {
+            Delimiter::None => None,
+            Delimiter::Padded(delimiter) => {
+                let found = delimiter.find(input)?;
+                let before = input[..found.start].trim_end_matches(is_padding);
+                let after = input[found.end..].trim_start_matches(is_padding);
+                Some(before.len()..input.len() - after.len())
+            }
+            Delimiter::Repeated(delimiter) => {
+                let found = delimiter.find(input)?;
+                let mut end = found.end;
+                while let Some(len) = delimiter.match_at(&input[end..]) {
+                    end += len;
+                }
+                Some(found.start..end)
+            }
+            _ => {
+                let text = self.text()?;
+                input.find(text).map(|i| i..i + text.len())
+            }
+        }
+    }
+
+    /// Length of the delimiter at the start of the input, `None` if it doesn't start with one
+    pub fn match_at(&self, input: &str) -> Option<usize> {
+        match self {
+            Delimiter::None => None,
+            Delimiter::Padded(delimiter) => {
+                let start = input.trim_start_matches(is_padding);
+                let rest = &start[delimiter.match_at(start)?..];
+                Some(input.len() - rest.trim_start_matches(is_padding).len())
+            }
+            Delimiter::Repeated(delimiter) => {
+                let mut end = delimiter.match_at(input)?;
+                while let Some(len) = delimiter.match_at(&input[end..]) {
+                    end += len;
+                }
+                Some(end)
+            }
+            _ => {
+                let text = self.text()?;
+                input.starts_with(text).then_some(text.len())
+            }
+        }
+    }
+}
+
+impl FromStr for SpecificationList {
+    type Err = SpecError;
+
+    fn from_str(specs: &str) -> std::result::Result<Self, SpecError> {
+        let mut lines = specs.lines().enumerate();
+        let (_, first) = lines.next().unwrap_or_default();
+        let mut specifications: Vec<Specification> = vec![first.parse()?];
+        let mut macro_delimiters = vec![];
+        let mut repeat_last = false;
+        while let Some((i, line)) = lines.next() {
+            if line == "..." {
+                let message = if macro_delimiters.is_empty() {
+                    "`...` repeats the last specification at a macro delimiter, there is none"
+                } else if lines.next().is_some() {
+                    "`...` has to be the last line"
+                } else {
+                    repeat_last = true;
+                    break;
+                };
+                return Err(SpecError::new(message, 0, 3)
+                    .with_source(line)
+                    .at_line(i + 1));
+            }
+            let delimiter: Delimiter = line.parse().map_err(|e: SpecError| e.at_line(i + 1))?;
+            // Sections can't be told apart without text between them
+            if delimiter.text().is_none() {
+                let found = if line.trim().is_empty() {
+                    "empty line".into()
+                } else {
+                    format!("`{}`", line.trim())
+                };
+                return Err(SpecError::new(
+                    format!("expected macro delimiter, found {found}"),
+                    0,
+                    line.chars().count().max(1),
+                )
+                .with_source(line)
+                .at_line(i + 1));
+            }
+            macro_delimiters.push(delimiter);
+            let Some((i, spec)) = lines.next() else {
+                return Err(SpecError::new(
+                    format!("expected specification line after macro delimiter `{line}`"),
+                    line.chars().count(),
+                    1,
+                )
+                .with_source(line)
+                .at_line(i + 1));
+            };
+            specifications.push(spec.parse().map_err(|e: SpecError| e.at_line(i + 1))?);
+        }
+        Ok(SpecificationList {
+            specifications,
+            macro_delimiters,
+            repeat_last,
+        })
+    }
+}
+
+pub fn parse_specs(specs: &str) -> std::result::Result<SpecificationList, SpecError> {
+    specs.parse()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn test_vec_vec_char() {
+        println!("{:?}", parse_specs("v nl {v  c}").unwrap())
+    }
+
+    #[test]
+    fn test_nested_containers() {
+        let specs = parse_specs("v nl {t \": \" {str} {hm , c {v sp i}}}\nnlnl\nset -> f").unwrap();
+        assert_eq!(
+            specs.specifications,
+            vec![
+                Specification {
+                    delimiter: Delimiter::NewLine,
+                    container: ContainerType::Vec(InnerType::Nested(Box::new(Specification {
+                        delimiter: Delimiter::Custom(": ".into()),
+                        container: ContainerType::Tuple(vec![
+                            InnerType::Nested(Box::new(Specification {
+                                delimiter: Delimiter::None,
+                                container: ContainerType::String,
+                            })),
+                            InnerType::Nested(Box::new(Specification {
+                                delimiter: Delimiter::Custom(",".into()),
+                                container: ContainerType::HashMap(
+                                    Delimiter::Space,
+                                    InnerType::Char,
+                                    InnerType::Nested(Box::new(Specification {
+                                        delimiter: Delimiter::Space,
+                                        container: ContainerType::Vec(InnerType::Integer(
+                                            IntType::I64
+                                        )),
+                                    })),
+                                ),
+                            })),
+                        ]),
+                    }))),
+                },
+                Specification {
+                    delimiter: Delimiter::Custom("->".into()),
+                    container: ContainerType::HashSet(InnerType::Float),
+                },
+            ]
+        );
+        assert_eq!(specs.macro_delimiters, vec![Delimiter::DoubleNewLine]);
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let spec = syn::parse_str::<Specification>("t \": \" ** {t nl *** * {v i}}").unwrap();
+        let ContainerType::Tuple(fields) = spec.container else {
+            panic!("Expected tuple, got {spec:?}");
+        };
+        assert_eq!(fields[0], InnerType::Wildcard(Wildcard::Chars));
+        let InnerType::Nested(nested) = &fields[1] else {
+            panic!("Expected nested spec, got {:?}", fields[1]);
+        };
+        assert_eq!(
+            nested.container,
+            ContainerType::Tuple(vec![
+                InnerType::Wildcard(Wildcard::Line),
+                InnerType::Wildcard(Wildcard::Char),
+                InnerType::Nested(Box::new(Specification {
+                    delimiter: Delimiter::None,
+                    container: ContainerType::Vec(InnerType::Integer(IntType::I64)),
+                })),
+            ])
+        );
+
+        assert!(syn::parse_str::<Specification>("v nl **").is_err());
+        assert!(syn::parse_str::<Specification>("t sp * ***").is_err());
+        assert!(syn::parse_str::<Specification>("t sp **** i").is_err());
+    }
+
+    #[test]
+    fn test_escaped_delimiters() {
+        let delimiter = |spec: &str| spec.parse::<Specification>().unwrap().delimiter;
+        assert_eq!(delimiter(r"t \s|\s i i"), Delimiter::Custom(" | ".into()));
+        assert_eq!(delimiter(r"v \{ c"), Delimiter::Custom("{".into()));
+        assert_eq!(delimiter(r"v a\,b c"), Delimiter::Custom("a,b".into()));
+        assert_eq!(
+            delimiter(r"v \u{2192}\t i"),
+            Delimiter::Custom("→\t".into())
+        );
+        assert_eq!(delimiter(r#"v "nl" i"#), Delimiter::Custom("nl".into()));
+
+        let nested = r"v nl {v \\ {t \s\{\s i i}}"
+            .parse::<Specification>()
+            .unwrap();
+        let ContainerType::Vec(InnerType::Nested(inner)) = nested.container else {
+            panic!("Expected nested vec, got {nested:?}");
+        };
+        assert_eq!(inner.delimiter, Delimiter::Custom("\\".into()));
+
+        let specs = parse_specs("str\n\\n---\\n\nv nl i").unwrap();
+        assert_eq!(
+            specs.macro_delimiters,
+            vec![Delimiter::Custom("\n---\n".into())]
+        );
+
+        assert!(r"v \q i".parse::<Specification>().is_err());
+        assert!(r"v \u{zz} i".parse::<Specification>().is_err());
+        assert!("a\\".parse::<Delimiter>().is_err());
+    }
+
+    #[test]
+    fn test_flexible_delimiters() {
+        let delimiter = |spec: &str| spec.parse::<Specification>().unwrap().delimiter;
+        let padded = |d: Delimiter| Delimiter::Padded(Box::new(d));
+        let repeated = |d: Delimiter| Delimiter::Repeated(Box::new(d));
+        let custom = |text: &str| Delimiter::Custom(text.into());
+        assert_eq!(delimiter("v sp+ i"), repeated(Delimiter::Space));
+        assert_eq!(delimiter("t ~|~ i i"), padded(custom("|")));
+        assert_eq!(delimiter("v ,+ i"), repeated(custom(",")));
+        assert_eq!(delimiter("v ~,+~ i"), padded(repeated(custom(","))));
+        assert_eq!(delimiter("v ~\"; \"~ i"), padded(custom("; ")));
+        assert_eq!(delimiter("v ~\",\"+~ i"), padded(repeated(custom(","))));
+        assert_eq!(delimiter("v ~sp~ i"), padded(Delimiter::Space));
+        assert_eq!(delimiter("v + i"), custom("+"));
+        assert_eq!("~<>~".parse::<Delimiter>().unwrap_err().column, 0);
+
+        let d = padded(repeated(custom(",")));
+        assert_eq!(d.text(), Some(","));
+        assert_eq!(d.find("a ,, b"), Some(1..5));
+        assert_eq!(d.match_at(" ,,b"), Some(3));
+        assert_eq!(d.match_at("b ,"), None);
+        let d = repeated(Delimiter::Space);
+        assert_eq!(d.find("1   2"), Some(1..4));
+        assert_eq!(d.match_at("  2"), Some(2));
+        assert_eq!(Delimiter::Space.find("1   2"), Some(1..2));
+    }
+
+    #[test]
+    fn test_maps() {
+        let spec = "hm nl \" = \" {str} {t \", \" {str} {str}}"
+            .parse::<Specification>()
+            .unwrap();
+        let ContainerType::HashMap(separator, InnerType::Nested(_), InnerType::Nested(_)) =
+            spec.container
+        else {
+            panic!("Expected map, got {spec:?}");
+        };
+        assert_eq!(separator, Delimiter::Custom(" = ".into()));
+
+        let spec = "mm ~,~ ~->~ c i".parse::<Specification>().unwrap();
+        assert_eq!(
+            spec.container,
+            ContainerType::MultiMap(
+                Delimiter::Padded(Box::new(Delimiter::Custom("->".into()))),
+                InnerType::Char,
+                InnerType::Integer(IntType::I64)
+            )
+        );
+        let spec = "hm , c i".parse::<Specification>().unwrap();
+        assert!(matches!(
+            spec.container,
+            ContainerType::HashMap(Delimiter::Space, ..)
+        ));
+
+        let err = parse_specs("hm , <> c i").unwrap_err();
+        assert_eq!(err.message, "key and value need a separator");
+        assert_eq!(err.column, 5);
+    }
+
+    #[test]
+    fn test_fixed_length() {
+        let spec = "t sp {str5} {v3 , u8}".parse::<Specification>().unwrap();
+        assert_eq!(
+            spec.container,
+            ContainerType::Tuple(vec![
+                InnerType::Nested(Box::new(Specification {
+                    delimiter: Delimiter::None,
+                    container: ContainerType::FixedString(5),
+                })),
+                InnerType::Nested(Box::new(Specification {
+                    delimiter: Delimiter::Custom(",".into()),
+                    container: ContainerType::Array(3, InnerType::Integer(IntType::U8)),
+                })),
+            ])
+        );
+        assert_eq!(
+            "v12 c".parse::<Specification>().unwrap().container,
+            ContainerType::Array(12, InnerType::Char)
+        );
+
+        let err = parse_specs("v0 sp i").unwrap_err();
+        assert_eq!(
+            err.message,
+            "fixed-length containers need at least one element"
+        );
+        let err = parse_specs("t3 sp i i i").unwrap_err();
+        assert!(err.message.starts_with("expected container"));
+    }
+
+    #[test]
+    fn test_sections() {
+        let specs =
+            parse_specs("sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl i}\n...").unwrap();
+        assert!(specs.repeat_last);
+        assert_eq!(specs.macro_delimiters, vec![Delimiter::DoubleNewLine]);
+        assert_eq!(
+            specs.specifications[1],
+            Specification {
+                delimiter: Delimiter::Custom(" map:\n".into()),
+                container: ContainerType::Section(InnerType::Nested(Box::new(Specification {
+                    delimiter: Delimiter::NewLine,
+                    container: ContainerType::Vec(InnerType::Integer(IntType::I64)),
+                }))),
+            }
+        );
+        assert!(!parse_specs("v sp i\nnl\nv sp i").unwrap().repeat_last);
+
+        let err = parse_specs("sec i").unwrap_err();
+        assert_eq!(err.message, "`sec` needs a delimiter after its title");
+        let err = parse_specs("v sp i\n...").unwrap_err();
+        assert_eq!(
+            err.message,
+            "`...` repeats the last specification at a macro delimiter, there is none"
+        );
+        assert_eq!((err.line, err.column), (2, 0));
+        let err = parse_specs("v sp i\nnl\nv sp i\n...\nnl").unwrap_err();
+        assert_eq!(err.message, "`...` has to be the last line");
+        assert_eq!(
+            parse_specs("v sp i\n\\...\nv sp i")
+                .unwrap()
+                .macro_delimiters,
+            vec![Delimiter::Custom("...".into())]
+        );
+
+        let err = parse_specs("v sp i\n\nv sp i").unwrap_err();
+        assert_eq!(err.message, "expected macro delimiter, found empty line");
+        assert_eq!((err.line, err.column), (2, 0));
+        let err = parse_specs("v sp i\n<>\nv sp i").unwrap_err();
+        assert_eq!(err.message, "expected macro delimiter, found `<>`");
+        assert_eq!((err.line, err.len), (2, 2));
+    }
+
+    #[test]
+    fn test_located_errors() {
+        let err = parse_specs("v nl {v }").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "error: expected inner type after `v`, found `}`\n \
+             --> spec:1:9\n  |\n1 | v nl {v }\n  |         ^"
+        );
+
+        let err = parse_specs("str\nnlnl\nt \\s\\q i i").unwrap_err();
+        assert_eq!(err.message, "unknown escape sequence `\\q`");
+        assert_eq!((err.line, err.column, err.len), (3, 4, 2));
+
+        let err = parse_specs(r"v nl {t \s|\s i (i)}").unwrap_err();
+        assert_eq!(err.message, "expected inner type after `t`, found `(`");
+        assert_eq!((err.column, err.len), (16, 1));
+
+        let err = parse_specs(r"t \s|\s ** i x").unwrap_err();
+        assert_eq!(err.message, "expected inner type after `t`, found `x`");
+        assert_eq!(err.column, 13);
+
+        let err = parse_specs("v sp").unwrap_err();
+        assert_eq!(
+            err.message,
+            "expected inner type after `v`, found end of spec"
+        );
+        assert_eq!(err.column, 4);
+
+        let err = parse_specs("v sp i\nnlnl").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+
+        let err = parse_specs("q nl i").unwrap_err();
+        assert_eq!(
+            err.message,
+            "expected container (`v`, `t`, `hm`, `mm`, `set`, `g`, `tpl`, `r`, `ri`, `rl`, `ch`, \
+             `sec` or `str`), found `q`"
+        );
+    }
+
+    #[test]
+    fn test_template() {
+        let spec = r#"tpl "Game " {id: u32} ": " {draws: v "; " {v ", " {t sp u32 {str}}}}"#
+            .parse::<Specification>()
+            .unwrap();
+        let ContainerType::Template(parts) = &spec.container else {
+            panic!("Expected template, got {spec:?}");
+        };
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0], TemplatePart::Literal("Game ".into()));
+        assert_eq!(
+            parts[1],
+            TemplatePart::Placeholder("id".into(), InnerType::Integer(IntType::U32))
+        );
+        assert_eq!(
+            parse_specs(&spec.to_string()).unwrap().specifications,
+            [spec]
+        );
+
+        let spec = r#"v nl {tpl {from} " = (" {left} ", " {right} ")"}"#;
+        assert_eq!(
+            parse_specs(spec).unwrap().to_string(),
+            r#"v nl {tpl {from: str} " = (" {left: str} ", " {right: str} ")"}"#
+        );
+
+        let err = parse_specs(r#"tpl {a} {b}"#).unwrap_err();
+        assert_eq!(err.message, "placeholders need literal text between them");
+        assert_eq!(err.column, 8);
+        let err = parse_specs(r#"tpl {a} "-" {a: i}"#).unwrap_err();
+        assert_eq!(err.message, "duplicate placeholder `a`");
+        assert!(parse_specs(r#"tpl "a""#).is_err());
+        assert!(parse_specs(r#"tpl {a: x}"#).is_err());
+        assert!(parse_specs(r#"tpl {a: i i}"#).is_err());
+    }
+
+    #[test]
+    fn test_int_types() {
+        let spec = "t sp i u8 {v , usize} i128"
+            .parse::<Specification>()
+            .unwrap();
+        assert_eq!(
+            spec.container,
+            ContainerType::Tuple(vec![
+                InnerType::Integer(IntType::I64),
+                InnerType::Integer(IntType::U8),
+                InnerType::Nested(Box::new(Specification {
+                    delimiter: Delimiter::Custom(",".into()),
+                    container: ContainerType::Vec(InnerType::Integer(IntType::Usize)),
+                })),
+                InnerType::Integer(IntType::I128),
+            ])
+        );
+        assert_eq!(spec.to_string(), "t sp i u8 {v \",\" usize} i128");
+        assert!("v sp u7".parse::<Specification>().is_err());
+    }
+
+    #[test]
+    fn test_grid_and_table() {
+        let spec = r"g ['.', '#' = 5, '\n' = -1, '\'']"
+            .parse::<Specification>()
+            .unwrap();
+        assert_eq!(
+            spec.container,
+            ContainerType::Grid(InnerType::Table(Table {
+                name: None,
+                entries: vec![
+                    (TableKey::Char('.'), 0),
+                    (TableKey::Char('#'), 5),
+                    (TableKey::Char('\n'), -1),
+                    (TableKey::Char('\''), 3)
+                ]
+            }))
+        );
+        assert_eq!(
+            spec.to_string(),
+            r"g ['.' = 0, '#' = 5, '\n' = -1, '\'' = 3]"
+        );
+        assert_eq!(
+            "g sp i".parse::<Specification>().unwrap().delimiter,
+            Delimiter::Space
+        );
+
+        let err = parse_specs("v ['a', 'a']").unwrap_err();
+        assert_eq!(err.message, "duplicate alternative 'a' in table");
+        assert_eq!((err.column, err.len), (8, 3));
+        assert!(parse_specs("v []").is_err());
+        assert!(parse_specs("v [a, 'b']").is_err());
+    }
+
+    #[test]
+    fn test_ranges_and_chunks() {
+        let spec = "t sp i {rl sp i}".parse::<Specification>().unwrap();
+        assert_eq!(
+            spec.container,
+            ContainerType::Tuple(vec![
+                InnerType::Integer(IntType::I64),
+                InnerType::Nested(Box::new(Specification {
+                    delimiter: Delimiter::Space,
+                    container: ContainerType::Range(RangeKind::Length, IntType::I64),
+                }))
+            ])
+        );
+        let spec = "ch 2 sp {ri - u32}".parse::<Specification>().unwrap();
+        assert_eq!(spec.to_string(), "ch 2 sp {ri \"-\" u32}");
+        assert_eq!(
+            parse_specs(&spec.to_string()).unwrap().specifications[0],
+            spec
+        );
+        assert_eq!(
+            "r .. i".parse::<Specification>().unwrap().to_string(),
+            "r \"..\" i"
+        );
+
+        let err = parse_specs("r i").unwrap_err();
+        assert_eq!(err.message, "`r` needs a delimiter between its numbers");
+        let err = parse_specs("rl sp f").unwrap_err();
+        assert_eq!(err.message, "ranges are of integers, found `f`");
+        assert!(parse_specs("ch 0 sp i").is_err());
+        assert!(parse_specs("ch sp i").is_err());
+    }
+
+    #[test]
+    fn test_alternations() {
+        let spec = r#"v ", " [Color: "red", "green", 'b']"#.parse::<Specification>().unwrap();
+        let ContainerType::Vec(InnerType::Table(table)) = &spec.container else {
+            panic!("expected table, got {spec:?}");
+        };
+        assert_eq!(table.name.as_deref(), Some("Color"));
+        assert_eq!(table.entries[1], (TableKey::Str("green".into()), 1));
+        assert_eq!(table.entries[2].0.text(), "b");
+        assert_eq!(spec.to_string(), r#"v ", " [Color: "red", "green", 'b']"#);
+
+        let spec = r#"v sp ["one" = 1, "two" = 2]"#.parse::<Specification>();
+        assert_eq!(spec.unwrap().to_string(), r#"v sp ["one" = 1, "two" = 2]"#);
+
+        let err = parse_specs(r#"v [Dir: "L" = 1]"#).unwrap_err();
+        assert_eq!(
+            err.message,
+            "the alternatives of `Dir` map to its variants, they have no values"
+        );
+        assert!(parse_specs(r#"v ["", "a"]"#).is_err());
+        assert!(parse_specs(r#"v ["ab", 'a', "ab"]"#).is_err());
+
+        // The alternatives of a named table can be left to its enum
+        let mut specs = parse_specs("t sp [Dir] {v [Color]}").unwrap();
+        assert_eq!(specs.to_string(), "t sp [Dir] {v [Color]}");
+        let tables = specs.tables_mut();
+        assert_eq!(tables.len(), 2);
+        assert!(tables[1].entries.is_empty());
+        assert_eq!(
+            parse_specs("v []").unwrap_err().message,
+            "table needs at least one entry"
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for specs in [
+            "v nl {t \": \" {str} {hm , c {v sp i}}}\nnlnl\nset -> f",
+            "t \": \" ** {t nl *** * {v i}}",
+            "v \"\\\\\" {t \"\\t{\" i c}\n\\n---\\n\nstr\n\\u{6e}l\nv sp i",
+            "t ~:~ ** {t ~|~ {v sp+ u32} {v ~\",\"+~ u32}}\n~nl~\nv ~sp~ i",
+            "hm nl \" = \" {str} {t , c i}\nnlnl\nmm <> = c i\nnl\nhm <> c i",
+            "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {v sp i}}\n...",
+            "v sp i\n\\...\nv sp i",
+            "v nl {t sp {str5} {v3 , u8}}\nnl\nv7 c",
+        ] {
+            let specs = parse_specs(specs).unwrap();
+            assert_eq!(parse_specs(&specs.to_string()).unwrap(), specs);
+        }
+        assert_eq!(
+            parse_specs("v nl {v  c}").unwrap().to_string(),
+            "v nl {v c}"
+        );
+        assert_eq!(escape(" nl"), "\\snl");
+        assert_eq!(escape("sp"), "\\u{73}p");
+        assert_eq!(escape("~x~"), "\\u{7e}x~");
+        assert_eq!(escape("a+"), "a\\u{2b}");
+
+        // Padded and repeated macro delimiters and literals that look like them
+        for delimiter in [
+            Delimiter::Padded(Box::new(Delimiter::Custom("x".into()))),
+            Delimiter::Repeated(Box::new(Delimiter::Custom("x".into()))),
+            Delimiter::Custom("a+".into()),
+            Delimiter::Custom("~x~".into()),
+            Delimiter::Padded(Box::new(Delimiter::Custom("a+".into()))),
+        ] {
+            let specs = SpecificationList {
+                specifications: vec![parse_specs("v sp i").unwrap().specifications[0].clone(); 2],
+                macro_delimiters: vec![delimiter],
+                repeat_last: false,
+            };
+            assert_eq!(parse_specs(&specs.to_string()).unwrap(), specs);
+        }
+        assert_eq!(
+            parse_specs("v sp i\n~x~\nv sp i\nx+\nv sp i")
+                .unwrap()
+                .to_string(),
+            "v sp i\n~x~\nv sp i\nx+\nv sp i"
+        );
+    }
+
+    #[test]
+    fn test_invalid_spec() {
+        assert!(syn::parse_str::<Specification>("v nl x").is_err());
+        assert!(syn::parse_str::<Specification>("q nl i").is_err());
+        assert!(syn::parse_str::<Specification>("v nl i i").is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::quote;
+use spec::{Delimiter, Specification};
+use syn::{
+    Attribute, Data, DataEnum, DeriveInput, Error, Fields, GenericParam, Lifetime, LifetimeParam,
+    LitStr, Result,
+};
+
+use crate::{once, parse_spec_lit, runtime_specs, spec_type};
+
+/// Values of the `#[spec(...)]` attributes of a struct or field
+#[derive(Default)]
+struct Attributes {
+    prefix: Option<String>,
+    suffix: Option<String>,
+    delimiter: Option<String>,
+    inner: Option<(Specification, LitStr)>,
+}
+
+/// Resolves a delimiter written like in specs to its text, `<>` to an empty string
+fn delimiter_text(lit: &LitStr) -> Result<String> {
+    let delimiter = lit
+        .value()
+        .parse::<Delimiter>()
+        .map_err(|e| Error::new(lit.span(), format!("invalid delimiter: {}", e.message)))?;
+    if delimiter.is_flexible() {
+        return Err(Error::new(
+            lit.span(),
+            "invalid delimiter: padded and repeated delimiters are only supported in specs",
+        ));
+    }
+    Ok(delimiter.text().unwrap_or_default().into())
+}
+
+fn parse_attributes(attrs: &[Attribute], is_field: bool) -> Result<Attributes> {
+    let mut attributes = Attributes::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("spec")) {
+        attr.parse_nested_meta(|meta| {
+            let lit = meta.value()?.parse::<LitStr>()?;
+            if meta.path.is_ident("prefix") {
+                attributes.prefix = Some(lit.value());
+            } else if meta.path.is_ident("delimiter") {
+                attributes.delimiter = Some(delimiter_text(&lit)?);
+            } else if is_field && meta.path.is_ident("suffix") {
+                attributes.suffix = Some(lit.value());
+            } else if is_field && meta.path.is_ident("inner") {
+                attributes.inner = Some((parse_spec_lit::<Specification>(&lit)?, lit));
+            } else {
+                return Err(meta.error(if is_field {
+                    "expected `prefix`, `suffix`, `delimiter` or `inner`"
+                } else {
+                    "expected `prefix` or `delimiter`"
+                }));
+            }
+            Ok(())
+        })?;
+    }
+    if attributes.delimiter.is_some() && attributes.inner.is_some() {
+        return Err(Error::new_spanned(
+            &attrs[0],
+            "`delimiter` and `inner` can't be combined, use a delimiter within the inner spec",
+        ));
+    }
+    Ok(attributes)
+}
+
+pub(crate) fn expand_derive_from_spec(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        Data::Enum(data) => return expand_enum(input, data),
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                name,
+                "`FromSpec` can only be derived for structs and enums",
+            ))
+        }
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            name,
+            "`FromSpec` can only be derived for structs with named fields",
+        ));
+    };
+    // A struct that borrows from the input does so with its first lifetime
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'input", Span::call_site());
+            generics
+                .params
+                .push(GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())));
+            lifetime
+        }
+    };
+    let string = if input.generics.lifetimes().next().is_some() {
+        quote!(&#lifetime str)
+    } else {
+        quote!(::std::string::String)
+    };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let attributes = parse_attributes(&input.attrs, false)?;
+    let delimiter = attributes.delimiter.unwrap_or_else(|| " ".into());
+
+    let mut steps = vec![];
+    if let Some(prefix) = &attributes.prefix {
+        steps.push(quote! {
+            let position = ::rust::from_spec::literal(input, position..end, #prefix, #name_str)?;
+        });
+    }
+    let mut idents = vec![];
+    for (i, field) in fields.named.iter().enumerate() {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let field_attributes = parse_attributes(&field.attrs, true)?;
+        let is_last = i + 1 == fields.named.len();
+
+        if let Some(prefix) = &field_attributes.prefix {
+            steps.push(quote! {
+                let position = ::rust::from_spec::literal(input, position..end, #prefix, #name_str)?;
+            });
+        }
+        // A field ends at its suffix, at the delimiter or at the end of the input
+        let terminator =
+            match (&field_attributes.suffix, is_last) {
+                (Some(suffix), _) => suffix.clone(),
+                (None, false) if delimiter.is_empty() => return Err(Error::new_spanned(
+                    ident,
+                    "fields of structs without delimiter need a `suffix`, except for the last one",
+                )),
+                (None, false) => delimiter.clone(),
+                (None, true) => String::new(),
+            };
+        let parse = match (&field_attributes.delimiter, &field_attributes.inner) {
+            (Some(element_delimiter), _) => quote! {
+                ::rust::from_spec::elements::<_, #ty>(input, position..field_end, #element_delimiter)?
+            },
+            (None, Some((spec, lit))) => {
+                let spec_ty = spec_type(spec, &string, lit.span())?;
+                let spec = runtime_specs(
+                    spec.clone().tables_mut(),
+                    quote! {
+                        #lit
+                            .parse::<::rust::Specification>()
+                            .expect("spec is checked at compile time")
+                    },
+                    lit.span(),
+                );
+                let spec = once(quote!(::rust::Specification), spec);
+                quote! {
+                    {
+                        let value: #spec_ty = ::rust::interpreter::FromValue::from_value(
+                            ::rust::interpreter::Interpret::interpret_range(
+                                #spec,
+                                input,
+                                position..field_end,
+                            )?,
+                        );
+                        value
+                    }
+                }
+            }
+            (None, None) => quote! {
+                <#ty as ::rust::from_spec::FromSpec<#lifetime>>::from_spec_range(input, position..field_end)?
+            },
+        };
+        steps.push(quote! {
+            let field_end = ::rust::from_spec::field_end(input, position..end, #terminator, #name_str)?;
+            let #ident = #parse;
+            let position = field_end + #terminator.len();
+        });
+        if field_attributes.suffix.is_some() && !is_last && !delimiter.is_empty() {
+            steps.push(quote! {
+                let position = ::rust::from_spec::literal(input, position..end, #delimiter, #name_str)?;
+            });
+        }
+        idents.push(ident);
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::rust::from_spec::FromSpec<#lifetime> for #name #ty_generics
+        #where_clause
+        {
+            fn from_spec_range(
+                input: &#lifetime str,
+                range: ::std::ops::Range<usize>,
+            ) -> ::std::result::Result<Self, ::rust::interpreter::ParseInputError> {
+                let end = range.end;
+                let position = range.start;
+                #(#steps)*
+                ::rust::from_spec::end(input, position..end, #name_str)?;
+                Ok(#name { #(#idents),* })
+            }
+        }
+    })
+}
+
+/// Fieldless enums are alternations of their variants' tokens, which are given by
+/// `#[spec(token = "...")]` and default to the variant names. Tables named by the enum are
+/// resolved by these tokens.
+fn expand_enum(input: &DeriveInput, data: &DataEnum) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`FromSpec` can't be derived for generic enums",
+        ));
+    }
+    let mut tokens = vec![];
+    let mut variants = vec![];
+    for variant in &data.variants {
+        if !variant.fields.is_empty() {
+            return Err(Error::new_spanned(
+                variant,
+                "`FromSpec` can only be derived for enums without fields",
+            ));
+        }
+        let mut token = None;
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("spec")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("token") {
+                    token = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `token`"))
+                }
+            })?;
+        }
+        let token = token.unwrap_or_else(|| variant.ident.to_string());
+        if token.is_empty() || tokens.contains(&token) {
+            return Err(Error::new_spanned(
+                variant,
+                format!("token {token:?} is empty or used by another variant"),
+            ));
+        }
+        tokens.push(token);
+        variants.push(&variant.ident);
+    }
+    let indices = (0..variants.len()).map(Literal::usize_unsuffixed);
+    let indices_from = indices.clone();
+    Ok(quote! {
+        impl ::rust::from_spec::Tokens for #name {
+            const TOKENS: &'static [&'static str] = &[#(#tokens),*];
+        }
+
+        impl<'input> ::rust::from_spec::FromSpec<'input> for #name {
+            fn from_spec_range(
+                input: &'input str,
+                range: ::std::ops::Range<usize>,
+            ) -> ::std::result::Result<Self, ::rust::interpreter::ParseInputError> {
+                let index =
+                    ::rust::from_spec::alternative(input, range, &[#(#tokens),*], #name_str)?;
+                Ok(match index {
+                    #(#indices => #name::#variants,)*
+                    _ => unreachable!(),
+                })
+            }
+        }
+
+        impl<'a> ::rust::interpreter::FromValue<'a> for #name {
+            fn from_value(value: ::rust::interpreter::Value<'a>) -> Self {
+                match ::rust::from_spec::alternative_index(&value, &[#(#tokens),*]) {
+                    #(Some(#indices_from) => #name::#variants,)*
+                    _ => panic!("Expected {}, got {value:?}", #name_str),
+                }
+            }
+        }
+
+        impl<'a> ::rust::interpreter::IntoValue<'a> for #name {
+            fn into_value(self) -> ::rust::interpreter::Value<'a> {
+                let token = match self {
+                    #(#name::#variants => #tokens,)*
+                };
+                ::rust::interpreter::Value::Str(::std::borrow::Cow::Borrowed(token))
+            }
+        }
+    })
+}
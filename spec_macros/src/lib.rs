@@ -0,0 +1,431 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use spec::{
+    ContainerType, InnerType, RangeKind, SpecError, Specification, SpecificationList, Table,
+    TemplatePart,
+};
+use std::str::FromStr;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, DeriveInput, Error, Expr, LitStr, Result, Token,
+};
+
+mod from_spec;
+
+struct ParseInput {
+    borrow: bool,
+    spec: LitStr,
+    input: Expr,
+}
+
+impl Parse for ParseInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let borrow = input.parse::<Option<Token![ref]>>()?.is_some();
+        let spec = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let content = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(ParseInput {
+            borrow,
+            spec,
+            input: content,
+        })
+    }
+}
+
+/// `parse_input!("v nl {v sp i}", &content)` evaluates to the content parsed as
+/// `Vec<Vec<i64>>`, or to a `rust::interpreter::ParseInputError` with the `InputError`
+/// locating where the content doesn't match.
+///
+/// With `parse_input!(ref "v nl {str}", &content)`, strings are `&str` borrowed from the
+/// content instead of owned `String`s.
+///
+/// The spec is checked and the result type is derived at compile time, the parsing itself
+/// is done by the interpreter in `rust::interpreter`. At runtime the spec is only parsed on
+/// the first use of a call, so calling it in a loop is cheap.
+#[proc_macro]
+pub fn parse_input(input: TokenStream) -> TokenStream {
+    let ParseInput {
+        borrow,
+        spec,
+        input,
+    } = parse_macro_input!(input as ParseInput);
+    let string = if borrow {
+        quote!(&str)
+    } else {
+        quote!(::std::string::String)
+    };
+    expand_parse_input(&spec, &input, &string)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// `parse_stream!("v nl {v sp i}", reader)` evaluates to an iterator over the lines of a
+/// `BufRead` parsed as `Vec<i64>`, see `rust::stream::Records`. Errors are
+/// `rust::stream::StreamError`s.
+#[proc_macro]
+pub fn parse_stream(input: TokenStream) -> TokenStream {
+    let ParseInput {
+        borrow,
+        spec,
+        input,
+    } = parse_macro_input!(input as ParseInput);
+    if borrow {
+        return Error::new(spec.span(), "streamed records can't borrow from the input")
+            .into_compile_error()
+            .into();
+    }
+    expand_parse_stream(&spec, &input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct NomParser {
+    borrow: bool,
+    spec: LitStr,
+}
+
+impl Parse for NomParser {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let borrow = input.parse::<Option<Token![ref]>>()?.is_some();
+        let spec = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(NomParser { borrow, spec })
+    }
+}
+
+/// `nom_parser!("v sp i")` evaluates to a nom parser `Fn(&str) -> IResult<&str, Vec<i64>, E>`
+/// compiled from the spec, see `rust::nom_parser::Compile`. Like in `parse_input!`, `ref`
+/// makes strings `&str`.
+#[proc_macro]
+pub fn nom_parser(input: TokenStream) -> TokenStream {
+    let NomParser { borrow, spec } = parse_macro_input!(input as NomParser);
+    let string = if borrow {
+        quote!(&str)
+    } else {
+        quote!(::std::string::String)
+    };
+    expand_nom_parser(&spec, &string)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Parses the spec of a string literal, errors are reported at the literal
+fn parse_spec_lit<T: FromStr<Err = SpecError>>(spec: &LitStr) -> Result<T> {
+    spec.value().parse::<T>().map_err(|e| {
+        Error::new(
+            error_span(spec, &e),
+            format!("invalid spec: {}\n{}", e.message, e.snippet()),
+        )
+    })
+}
+
+/// Span of the part of the literal that an error points at, where the compiler supports
+/// spans within literals. Literals with escape sequences are located as a whole, the columns
+/// of their value aren't the ones of their source.
+fn error_span(spec: &LitStr, e: &SpecError) -> Span {
+    let source = spec.token().to_string();
+    let value = spec.value();
+    if source.get(1..source.len() - 1) != Some(value.as_str()) {
+        return spec.span();
+    }
+    let line_start = value
+        .split('\n')
+        .take(e.line - 1)
+        .map(|line| line.len() + 1)
+        .sum::<usize>();
+    let line = value[line_start..].split('\n').next().unwrap_or_default();
+    let byte = |column: usize| {
+        line.char_indices()
+            .nth(column)
+            .map_or(line.len(), |(i, _)| i)
+    };
+    // The value starts after the opening quote
+    let start = 1 + line_start + byte(e.column);
+    let end = 1 + line_start + byte(e.column + e.len);
+    spec.token()
+        .subspan(start..end.max(start + 1))
+        .unwrap_or_else(|| spec.span())
+}
+
+/// Expression of the specs at runtime. The alternatives of tables named by enums are taken
+/// from the tokens of the enums, alternatives written in the spec are checked against them
+/// at compile time.
+fn runtime_specs(tables: Vec<&mut Table>, parse: TokenStream2, span: Span) -> TokenStream2 {
+    let mut checks = vec![];
+    let mut names: Vec<String> = vec![];
+    for table in tables {
+        let Some(name) = table.name.as_deref() else {
+            continue;
+        };
+        let ident = Ident::new(name, span);
+        if !table.entries.is_empty() {
+            let alternatives = table.entries.iter().map(|(key, _)| key.text().into_owned());
+            let message = format!("the alternatives of `{name}` in the spec aren't its tokens");
+            checks.push(quote! {
+                const _: () = ::std::assert!(
+                    ::rust::from_spec::same_tokens(
+                        <#ident as ::rust::from_spec::Tokens>::TOKENS,
+                        &[#(#alternatives),*],
+                    ),
+                    #message,
+                );
+            });
+        }
+        if !names.iter().any(|other| other == name) {
+            names.push(name.into());
+        }
+    }
+    if names.is_empty() {
+        return parse;
+    }
+    let idents = names.iter().map(|name| Ident::new(name, span));
+    quote! {
+        {
+            #(#checks)*
+            let mut specs = #parse;
+            #(::rust::from_spec::resolve_tables(
+                specs.tables_mut(),
+                #names,
+                <#idents as ::rust::from_spec::Tokens>::TOKENS,
+            );)*
+            specs
+        }
+    }
+}
+
+/// Expression of a `&'static` reference to the value of `init` of type `ty`, which is only
+/// evaluated on first use. Specs are parsed once rather than on every call.
+fn once(ty: TokenStream2, init: TokenStream2) -> TokenStream2 {
+    quote! {
+        {
+            static VALUE: ::std::sync::OnceLock<#ty> = ::std::sync::OnceLock::new();
+            VALUE.get_or_init(|| #init)
+        }
+    }
+}
+
+fn expand_parse_input(spec: &LitStr, input: &Expr, string: &TokenStream2) -> Result<TokenStream2> {
+    let mut specs = parse_spec_lit::<SpecificationList>(spec)?;
+    let ty = list_type(&specs, string, spec.span())?;
+    let specs = runtime_specs(
+        specs.tables_mut(),
+        quote!(::rust::parse_specs(#spec).expect("spec is checked at compile time")),
+        spec.span(),
+    );
+    let specs = once(quote!(::rust::SpecificationList), specs);
+    Ok(quote! {
+        ::rust::interpreter::Interpret::interpret(#specs, #input)
+            .map(<#ty as ::rust::interpreter::FromValue<'_>>::from_value)
+    })
+}
+
+fn expand_nom_parser(spec: &LitStr, string: &TokenStream2) -> Result<TokenStream2> {
+    let mut specs = parse_spec_lit::<SpecificationList>(spec)?;
+    let ty = list_type(&specs, string, spec.span())?;
+    let specs = runtime_specs(
+        specs.tables_mut(),
+        quote!(::rust::parse_specs(#spec).expect("spec is checked at compile time")),
+        spec.span(),
+    );
+    let specs = once(quote!(::rust::SpecificationList), specs);
+    Ok(quote! {
+        ::rust::nom_parser::typed::<#ty, _>(::rust::nom_parser::Compile::compile(#specs))
+    })
+}
+
+fn expand_parse_stream(spec: &LitStr, input: &Expr) -> Result<TokenStream2> {
+    let mut parsed = parse_spec_lit::<Specification>(spec)?;
+    let (ContainerType::Vec(inner), Some(_), false) = (
+        &parsed.container,
+        parsed.delimiter.text(),
+        parsed.delimiter.is_flexible(),
+    ) else {
+        return Err(Error::new(
+            spec.span(),
+            "invalid spec: only `v` with a fixed delimiter can be streamed",
+        ));
+    };
+    let ty = inner_type(inner, &quote!(::std::string::String), spec.span())?;
+    let parsed = runtime_specs(
+        parsed.tables_mut(),
+        quote! {
+            #spec
+                .parse::<::rust::Specification>()
+                .expect("spec is checked at compile time")
+        },
+        spec.span(),
+    );
+    Ok(quote! {
+        ::rust::stream::Records::new(&#parsed, #input)
+        .expect("spec is checked at compile time")
+        .map(|record| {
+            record.map(<#ty as ::rust::interpreter::FromValue<'static>>::from_value)
+        })
+    })
+}
+
+/// `#[derive(FromSpec)]` implements `rust::from_spec::FromSpec` for a struct with named
+/// fields, see there for the `#[spec(...)]` attributes.
+#[proc_macro_derive(FromSpec, attributes(spec))]
+pub fn derive_from_spec(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_spec::expand_derive_from_spec(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Rust type of the value of the specs, `string` is the type of `str`
+fn list_type(specs: &SpecificationList, string: &TokenStream2, span: Span) -> Result<TokenStream2> {
+    let mut section_types = specs
+        .specifications
+        .iter()
+        .map(|spec| spec_type(spec, string, span))
+        .collect::<Result<Vec<_>>>()?;
+    // The repeated last sections are collected in a list
+    if specs.repeat_last {
+        let last = section_types.pop().unwrap();
+        section_types.push(quote!(::std::vec::Vec<#last>));
+    }
+    Ok(match &section_types[..] {
+        [ty] => ty.clone(),
+        _ => quote!((#(#section_types,)*)),
+    })
+}
+
+fn spec_type(spec: &Specification, string: &TokenStream2, span: Span) -> Result<TokenStream2> {
+    Ok(match &spec.container {
+        ContainerType::Vec(inner) | ContainerType::Chunks(_, inner) => {
+            let inner = inner_type(inner, string, span)?;
+            quote!(::std::vec::Vec<#inner>)
+        }
+        ContainerType::Array(len, inner) => {
+            let inner = inner_type(inner, string, span)?;
+            quote!([#inner; #len])
+        }
+        ContainerType::Range(kind, ty) => {
+            let ty = proc_macro2::Ident::new(ty.name(), span);
+            match kind {
+                RangeKind::Inclusive => quote!(::std::ops::RangeInclusive<#ty>),
+                RangeKind::Exclusive | RangeKind::Length => quote!(::std::ops::Range<#ty>),
+            }
+        }
+        // Wildcards are left out, a single remaining field is not wrapped in a tuple
+        ContainerType::Tuple(inners) => {
+            let inners = inners
+                .iter()
+                .filter(|inner| !matches!(inner, InnerType::Wildcard(_)))
+                .map(|inner| inner_type(inner, string, span))
+                .collect::<Result<Vec<_>>>()?;
+            match &inners[..] {
+                [inner] => inner.clone(),
+                _ => quote!((#(#inners,)*)),
+            }
+        }
+        ContainerType::HashMap(_, key, value) => {
+            check_hashable(key, span)?;
+            let key = inner_type(key, string, span)?;
+            let value = inner_type(value, string, span)?;
+            quote!(::std::collections::HashMap<#key, #value>)
+        }
+        ContainerType::MultiMap(_, key, value) => {
+            check_hashable(key, span)?;
+            let key = inner_type(key, string, span)?;
+            let value = inner_type(value, string, span)?;
+            quote!(::std::collections::HashMap<#key, ::std::vec::Vec<#value>>)
+        }
+        ContainerType::HashSet(inner) => {
+            check_hashable(inner, span)?;
+            let inner = inner_type(inner, string, span)?;
+            quote!(::std::collections::HashSet<#inner>)
+        }
+        ContainerType::Grid(inner) => {
+            let inner = inner_type(inner, string, span)?;
+            quote!(::rust::grid::Grid<#inner>)
+        }
+        // Templates are tuples of their fields, even with a single field
+        ContainerType::Template(parts) => {
+            let fields = parts
+                .iter()
+                .filter_map(|part| match part {
+                    TemplatePart::Placeholder(_, inner) => Some(inner_type(inner, string, span)),
+                    TemplatePart::Literal(_) => None,
+                })
+                .collect::<Result<Vec<_>>>()?;
+            quote!((#(#fields,)*))
+        }
+        ContainerType::Section(inner) => {
+            let inner = inner_type(inner, string, span)?;
+            quote!((#string, #inner))
+        }
+        ContainerType::String => string.clone(),
+        // Fixed-length strings are arrays of their chars
+        ContainerType::FixedString(len) => quote!([char; #len]),
+    })
+}
+
+fn inner_type(inner: &InnerType, string: &TokenStream2, span: Span) -> Result<TokenStream2> {
+    Ok(match inner {
+        InnerType::Integer(ty) => {
+            let ty = proc_macro2::Ident::new(ty.name(), span);
+            quote!(#ty)
+        }
+        InnerType::Table(Table { name: None, .. }) => quote!(i64),
+        InnerType::Table(Table {
+            name: Some(name), ..
+        }) => {
+            let name = proc_macro2::Ident::new(name, span);
+            quote!(#name)
+        }
+        InnerType::Float => quote!(f64),
+        InnerType::Char => quote!(char),
+        InnerType::Nested(spec) => spec_type(spec, string, span)?,
+        InnerType::Wildcard(_) => {
+            return Err(Error::new(
+                span,
+                "invalid spec: wildcards are only allowed as tuple fields",
+            ))
+        }
+    })
+}
+
+/// Set elements and map keys have to implement `Hash`
+fn check_hashable(inner: &InnerType, span: Span) -> Result<()> {
+    fn is_hashable(inner: &InnerType) -> bool {
+        match inner {
+            InnerType::Integer(_)
+            | InnerType::Char
+            | InnerType::Wildcard(_)
+            | InnerType::Table(_) => true,
+            InnerType::Float => false,
+            InnerType::Nested(spec) => match &spec.container {
+                ContainerType::Vec(inner)
+                | ContainerType::Grid(inner)
+                | ContainerType::Array(_, inner)
+                | ContainerType::Chunks(_, inner)
+                | ContainerType::Section(inner) => is_hashable(inner),
+                ContainerType::Tuple(inners) => inners.iter().all(is_hashable),
+                ContainerType::Template(parts) => parts.iter().all(|part| match part {
+                    TemplatePart::Placeholder(_, inner) => is_hashable(inner),
+                    TemplatePart::Literal(_) => true,
+                }),
+                ContainerType::HashMap(..)
+                | ContainerType::MultiMap(..)
+                | ContainerType::HashSet(_) => false,
+                ContainerType::Range(..)
+                | ContainerType::String
+                | ContainerType::FixedString(_) => true,
+            },
+        }
+    }
+
+    if is_hashable(inner) {
+        Ok(())
+    } else {
+        Err(Error::new(
+            span,
+            "invalid spec: floats, sets and maps can't be set elements or map keys",
+        ))
+    }
+}
@@ -1,6 +1,7 @@
 use std::{error::Error, fs::File, io::Read};
 
 use itertools::Itertools;
+use rust::parse_input;
 
 fn taxicab_11(((x1, y1), (x2, y2)): (&(usize, usize), &(usize, usize))) -> usize {
     x1.abs_diff(*x2) + y1.abs_diff(*y2)
@@ -9,19 +10,15 @@ fn taxicab_11(((x1, y1), (x2, y2)): (&(usize, usize), &(usize, usize))) -> usize
 fn main() -> Result<(), Box<dyn Error>> {
     let mut file = String::new();
     File::open("in/11r.txt")?.read_to_string(&mut file)?;
-    let positions = file
-        .lines()
-        .enumerate()
-        .flat_map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .filter_map(move |(x, c)| (c == '#').then_some((x, y)))
-        })
+    let image = parse_input!("g c", &file)?;
+    let positions = image
+        .positions()
+        .filter_map(|((y, x), c)| (*c == '#').then_some((x, y)))
         .collect_vec();
-    let empty_rows = (0..file.lines().count())
+    let empty_rows = (0..image.height())
         .filter(|y| positions.iter().all(|(_, y2)| y != y2))
         .collect_vec();
-    let empty_cols = (0..file.lines().next().unwrap().len())
+    let empty_cols = (0..image.width())
         .filter(|x| positions.iter().all(|(x2, _)| x != x2))
         .collect_vec();
 
@@ -5,8 +5,8 @@ use itertools::Itertools;
 
 use std::{error::Error, fs::File, io::Read, ops::Range};
 
-fn is_symbol(c: &char) -> bool {
-    !c.is_ascii_digit() && *c != '.'
+fn is_symbol(c: char) -> bool {
+    !c.is_ascii_digit() && c != '.'
 }
 
 fn nums_of_line(line: &[char]) -> Vec<(u32, Range<usize>)> {
@@ -37,16 +37,20 @@ fn nums_of_line(line: &[char]) -> Vec<(u32, Range<usize>)> {
 }
 
 fn check_line_for_symbol(line: &[char], r: &Range<usize>) -> bool {
-    line.iter().skip(r.start).take(r.len()).any(is_symbol)
-        || (r.start > 0 && is_symbol(&line[r.start - 1]))
-        || (r.end < line.len() && is_symbol(&line[r.end]))
+    line.iter()
+        .skip(r.start)
+        .take(r.len())
+        .copied()
+        .any(is_symbol)
+        || (r.start > 0 && is_symbol(line[r.start - 1]))
+        || (r.end < line.len() && is_symbol(line[r.end]))
 }
 
 fn has_symbol(symbols: &[Vec<char>], k: usize, r: &Range<usize>) -> bool {
     (k < symbols.len() - 1 && check_line_for_symbol(&symbols[k + 1], r))
         || (k > 0 && check_line_for_symbol(&symbols[k - 1], r))
-        || (r.start > 0 && is_symbol(&symbols[k][r.start - 1]))
-        || (r.end < symbols[0].len() && is_symbol(&symbols[k][r.end]))
+        || (r.start > 0 && is_symbol(symbols[k][r.start - 1]))
+        || (r.end < symbols[0].len() && is_symbol(symbols[k][r.end]))
 }
 
 fn gears(symbols: &[Vec<(u32, Range<usize>)>], k: usize, i: usize) -> Option<u64> {
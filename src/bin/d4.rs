@@ -1,37 +1,19 @@
+use rust::parse_input;
 use std::{collections::HashSet, error::Error, fs::File, io::Read};
 
-fn get_winning_given(line: &str) -> (HashSet<u32>, Vec<u32>) {
-    let interesting = line.split_once(':').unwrap().1.trim();
-    let (winning, given) = interesting.split_once(" | ").unwrap();
-    let winning = winning
-        .split_ascii_whitespace()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-    let given = given
-        .split_ascii_whitespace()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-    (winning, given)
-}
+type Card = (HashSet<u32>, Vec<u32>);
 
-fn count_winning(line: &str) -> usize {
-    let (winning, given) = get_winning_given(line);
+fn count_winning((winning, given): &Card) -> usize {
     given.iter().filter(|n| winning.contains(n)).count()
 }
 
-fn parse_line_1(line: &str) -> u32 {
-    (1 << count_winning(line)) >> 1
-}
-
-fn parse_2(content: &str) -> u32 {
+fn parse_2(cards: &[Card]) -> u32 {
     let mut total_count = 0;
-    let mut counts = vec![1; content.lines().count()];
-    for (k, line) in content.lines().enumerate() {
+    let mut counts = vec![1; cards.len()];
+    for (k, card) in cards.iter().enumerate() {
         let card_count = counts[k];
         total_count += card_count;
-        for v in counts.iter_mut().skip(k + 1).take(count_winning(line)) {
+        for v in counts.iter_mut().skip(k + 1).take(count_winning(card)) {
             *v += card_count;
         }
     }
@@ -41,10 +23,17 @@ fn parse_2(content: &str) -> u32 {
 fn main() -> Result<(), Box<dyn Error>> {
     let mut content = String::new();
     File::open("in/4r.txt")?.read_to_string(&mut content)?;
+    let cards = parse_input!(
+        "v nl {t ~:~ ** {t ~|~ {set sp+ u32} {v sp+ u32}}}",
+        &content
+    )?;
     println!(
         "Total (1): {}",
-        content.lines().map(parse_line_1).sum::<u32>()
+        cards
+            .iter()
+            .map(|card| (1 << count_winning(card)) >> 1)
+            .sum::<u32>()
     );
-    println!("Total (2): {}", parse_2(&content));
+    println!("Total (2): {}", parse_2(&cards));
     Ok(())
 }
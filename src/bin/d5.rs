@@ -4,17 +4,7 @@
 use std::{error::Error, fs::File, io::Read, ops::Range};
 
 use itertools::Itertools;
-
-fn parse_converter(line: &str) -> (Range<i64>, i64) {
-    let [dest_start, source_start, len] = line
-        .split_ascii_whitespace()
-        .map(|d| d.parse().unwrap())
-        .collect_vec()[..]
-    else {
-        panic!("Invalid line {line}")
-    };
-    (source_start..source_start + len, dest_start - source_start)
-}
+use rust::parse_input;
 
 fn convert(mut value: i64, conversion: &[(Range<i64>, i64)]) -> i64 {
     if let Some((_, delta)) = conversion.iter().find(|(range, _)| range.contains(&value)) {
@@ -62,28 +52,27 @@ fn convert_range(mut r: Range<i64>, conversion: &[(Range<i64>, i64)]) -> Vec<Ran
 fn main() -> Result<(), Box<dyn Error>> {
     let mut content = String::new();
     File::open("in/5r.txt")?.read_to_string(&mut content)?;
-    let mut blocks = content.split("\n\n");
-    let seeds: Vec<i64> = blocks
-        .next()
-        .unwrap()
-        .split_once(": ")
-        .unwrap()
-        .1
-        .split_ascii_whitespace()
-        .map(str::parse)
-        .collect::<Result<_, _>>()
-        .unwrap();
-
-    let conversions = blocks
-        .map(|b| b.lines().skip(1).map(parse_converter).collect_vec())
+    let ((_, seeds), conversions) = parse_input!(
+        "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {t sp i {rl sp i}}}\n...",
+        &content
+    )?;
+    let conversions = conversions
+        .into_iter()
+        .map(|(_, conversion)| {
+            conversion
+                .into_iter()
+                .map(|(dest_start, source)| {
+                    let delta = dest_start - source.start;
+                    (source, delta)
+                })
+                .collect_vec()
+        })
         .collect_vec();
 
     part_1(&seeds, &conversions);
     let seeds = seeds
-        .iter()
-        .step_by(2)
-        .zip(seeds.iter().skip(1).step_by(2))
-        .map(|(&base, &len)| base..base + len)
+        .chunks(2)
+        .map(|pair| pair[0]..pair[0] + pair[1])
         .collect_vec();
 
     let converted = conversions.iter().fold(seeds, |seeds, conv| {
@@ -7,6 +7,7 @@ use std::{
 };
 
 use itertools::Itertools;
+use rust::parse_input;
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 enum Value {
@@ -224,16 +225,15 @@ impl Values {
     }
 }
 
-fn parse_line(line: &str) -> (Hand, u64) {
-    let (hand, value) = line.split_once(' ').unwrap();
-    (hand.chars().map(Value::from).into(), value.parse().unwrap())
+fn parse_line((cards, bet): ([char; 5], u64)) -> (Hand, u64) {
+    (cards.into_iter().map(Value::from).into(), bet)
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let mut file = String::new();
     File::open("in/7r.txt")?.read_to_string(&mut file)?;
-    let total = file
-        .lines()
+    let total = parse_input!("v nl {t sp {str5} u64}", &file)?
+        .into_iter()
         .map(parse_line)
         .sorted_by(|(h1, _), (h2, _)| h1.cmp(h2))
         .map(|s| {
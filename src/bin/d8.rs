@@ -1,12 +1,6 @@
 use std::{collections::HashMap, error::Error, fs::File, io::Read};
 
-use itertools::Itertools;
-
-fn parse_node(node: &str) -> (&str, (&str, &str)) {
-    let (from, to) = node.split_once(" = ").unwrap();
-    let (to_l, to_r) = to[1..to.len() - 1].split_once(", ").unwrap();
-    (from, (to_l, to_r))
-}
+use rust::parse_input;
 
 fn periodicity<'a>(
     mut node: &'a str,
@@ -32,9 +26,10 @@ fn periodicity<'a>(
 fn main() -> Result<(), Box<dyn Error>> {
     let mut file = String::new();
     File::open("in/8r.txt")?.read_to_string(&mut file)?;
-    let mut lines = file.lines();
-    let directions = lines.next().unwrap().chars().collect_vec();
-    let nodes: HashMap<_, _> = lines.skip(1).map(parse_node).collect();
+    let (directions, nodes) = parse_input!(
+        ref "v c\nnlnl\nhm nl \" = \" {str} {tpl \"(\" {left} \", \" {right} \")\"}",
+        &file
+    )?;
     let count = periodicity("AAA", &nodes, &directions, |n| n == "ZZZ");
     println!("Total (1): {count}");
 
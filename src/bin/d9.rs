@@ -2,19 +2,15 @@ use std::{error::Error, fs::File, io::Read};
 
 use bacon_sci::interp::lagrange;
 use itertools::Itertools;
+use rust::parse_input;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let mut file = String::new();
     File::open("in/9r.txt")?.read_to_string(&mut file)?;
 
-    let (sum_1, sum_2): (i64, i64) = file
-        .lines()
-        .map(|l| {
-            let ns = l
-                .split_ascii_whitespace()
-                .map(str::parse)
-                .collect::<Result<Vec<f64>, _>>()
-                .unwrap();
+    let (sum_1, sum_2): (i64, i64) = parse_input!("v nl {v sp f}", &file)?
+        .into_iter()
+        .map(|ns| {
             let poly = lagrange(
                 &(0..ns.len() as u32).map(f64::from).collect_vec(),
                 &ns,
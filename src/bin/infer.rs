@@ -0,0 +1,13 @@
+use std::{env, error::Error, fs::File, io::Read};
+
+use rust::infer::infer_specs;
+
+/// Prints the inferred spec of the input file given as argument, e.g. `in/5r.txt`
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = env::args().nth(1).ok_or("Usage: infer <input file>")?;
+    let mut file = String::new();
+    File::open(path)?.read_to_string(&mut file)?;
+
+    println!("{}", infer_specs(&file));
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::{env, error::Error, fs, path::PathBuf, process::ExitCode};
+
+use rust::{
+    dump,
+    interpreter::{Interpret, ParseInputError},
+    spec_file::{read_spec, spec_path},
+};
+
+const USAGE: &str = "Usage: parse [--dump [tree|json]] <input file> [spec file]";
+
+/// Parses an input file with its spec, e.g. `parse --dump in/5t.txt`. The spec file defaults
+/// to the one of the day, `in/5.spec`. Prints the parsed value as an indented tree or as
+/// JSON with `--dump`, only whether the input matches otherwise.
+fn main() -> ExitCode {
+    // Errors are printed like by rustc rather than debug formatted
+    match parse() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1).peekable();
+    let format = if args.next_if(|arg| arg == "--dump").is_some() {
+        Some(
+            args.next_if(|arg| arg == "tree" || arg == "json")
+                .unwrap_or("tree".into()),
+        )
+    } else {
+        None
+    };
+    let input_path = PathBuf::from(args.next().ok_or(USAGE)?);
+    let spec_path = args
+        .next()
+        .map_or_else(|| spec_path(&input_path), PathBuf::from);
+    if args.next().is_some() {
+        return Err(USAGE.into());
+    }
+
+    let specs = read_spec(&spec_path)?;
+    let input = fs::read_to_string(&input_path)?;
+    let value = specs.interpret(&input).map_err(|e| match e {
+        ParseInputError::Input(e) => e.in_file(&input_path),
+        ParseInputError::Spec(e) => e.to_string(),
+    })?;
+    match format.as_deref() {
+        Some("json") => println!("{}", dump::json(&value)),
+        Some(_) => print!("{}", dump::tree(&value)),
+        None => println!("{}: matches {}", input_path.display(), spec_path.display()),
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::{env, error::Error, fs, path::Path, process::ExitCode};
+
+use rust::{
+    spec_file::{read_spec, spec_path},
+    validate::validate,
+};
+
+/// Day number and name of an input file, so that `2r.txt` comes before `10r.txt`
+fn sort_key(path: &Path) -> (u32, String) {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let day = name
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>();
+    (day.parse().unwrap_or(u32::MAX), name.into_owned())
+}
+
+/// Checks every input file of a directory, `in` by default, against the spec file of its day:
+/// `validate` or `validate in`. Prints the problems of each file and fails if there are any.
+fn main() -> ExitCode {
+    match check_all() {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Number of invalid input files
+fn check_all() -> Result<usize, Box<dyn Error>> {
+    let dir = env::args().nth(1).unwrap_or("in".into());
+    let mut inputs = fs::read_dir(&dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    inputs.retain(|path| path.extension().is_some_and(|ext| ext == "txt"));
+    inputs.sort_by_key(|path| sort_key(path));
+
+    let mut invalid = 0;
+    for input_path in &inputs {
+        let problems = match read_spec(&spec_path(input_path)) {
+            Ok(specs) => validate(&specs, &fs::read_to_string(input_path)?)?
+                .iter()
+                .map(|e| e.in_file(input_path))
+                .collect(),
+            Err(e) => vec![e.to_string()],
+        };
+        if problems.is_empty() {
+            println!("ok      {}", input_path.display());
+        } else {
+            invalid += 1;
+            println!("invalid {}", input_path.display());
+            for problem in problems {
+                println!("{problem}\n");
+            }
+        }
+    }
+    println!("{} inputs, {invalid} invalid", inputs.len());
+    Ok(invalid)
+}
@@ -0,0 +1,288 @@
+use std::fmt::Write;
+
+use crate::interpreter::Value;
+
+// Maps and sets have no order, their entries are sorted by their text so that dumps of the
+// same input are the same.
+
+/// Scalars and ranges, and lists of scalars, which are written on a single line
+fn inline(value: &Value) -> Option<String> {
+    let is_scalar = |value: &Value| {
+        !matches!(
+            value,
+            Value::Vec(_)
+                | Value::Tuple(_)
+                | Value::Record(_)
+                | Value::Section(..)
+                | Value::Map(_)
+                | Value::Set(_)
+                | Value::Grid(_)
+        )
+    };
+    Some(match value {
+        Value::Vec(values) | Value::Tuple(values) if values.iter().all(is_scalar) => {
+            let values = values.iter().map(|v| inline(v).unwrap());
+            let values = values.collect::<Vec<_>>().join(", ");
+            match value {
+                Value::Vec(_) => format!("[{values}]"),
+                _ => format!("({values})"),
+            }
+        }
+        Value::Range(start, end) => format!("{}..{}", inline(start)?, inline(end)?),
+        Value::RangeInclusive(start, end) => format!("{}..={}", inline(start)?, inline(end)?),
+        Value::Str(s) => format!("{s:?}"),
+        Value::Int(i) => i.to_string(),
+        Value::UInt(u) => u.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Char(c) => format!("{c:?}"),
+        _ => return None,
+    })
+}
+
+fn sorted<T>(entries: impl IntoIterator<Item = T>, key: impl Fn(&T) -> String) -> Vec<T> {
+    let mut entries = entries.into_iter().collect::<Vec<_>>();
+    entries.sort_by_cached_key(key);
+    entries
+}
+
+fn write_tree(value: &Value, label: &str, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    if let Some(text) = inline(value) {
+        writeln!(out, "{indent}{label}{text}").unwrap();
+        return;
+    }
+    let elements = |values: &[Value], out: &mut String| {
+        for (i, value) in values.iter().enumerate() {
+            write_tree(value, &format!("[{i}] "), depth + 1, out);
+        }
+    };
+    match value {
+        Value::Vec(values) => {
+            writeln!(out, "{indent}{label}vec ({})", values.len()).unwrap();
+            elements(values, out);
+        }
+        Value::Tuple(values) => {
+            writeln!(out, "{indent}{label}tuple ({})", values.len()).unwrap();
+            elements(values, out);
+        }
+        Value::Record(fields) => {
+            writeln!(out, "{indent}{label}record").unwrap();
+            for (name, value) in fields {
+                write_tree(value, &format!("{name}: "), depth + 1, out);
+            }
+        }
+        Value::Section(title, content) => {
+            writeln!(out, "{indent}{label}section {title:?}").unwrap();
+            write_tree(content, "", depth + 1, out);
+        }
+        Value::Map(entries) => {
+            writeln!(out, "{indent}{label}map ({})", entries.len()).unwrap();
+            let key = |key: &Value| inline(key).unwrap_or_else(|| json(key));
+            for (k, v) in sorted(entries, |(k, _)| key(k)) {
+                write_tree(v, &format!("{} => ", key(k)), depth + 1, out);
+            }
+        }
+        Value::Set(values) => {
+            writeln!(out, "{indent}{label}set ({})", values.len()).unwrap();
+            for value in sorted(values, |v| json(v)) {
+                write_tree(value, "- ", depth + 1, out);
+            }
+        }
+        Value::Grid(grid) => {
+            writeln!(
+                out,
+                "{indent}{label}grid {}x{}",
+                grid.height(),
+                grid.width()
+            )
+            .unwrap();
+            // Rows of chars are written as they are in the input
+            for (i, row) in grid.rows().enumerate() {
+                let row = if row.iter().all(|cell| matches!(cell, Value::Char(_))) {
+                    row.iter().filter_map(Value::as_char).collect::<String>()
+                } else {
+                    let cells = row
+                        .iter()
+                        .map(|cell| inline(cell).unwrap_or_else(|| json(cell)));
+                    cells.collect::<Vec<_>>().join(" ")
+                };
+                writeln!(out, "{indent}  [{i}] {row}").unwrap();
+            }
+        }
+        _ => unreachable!("scalars are inline"),
+    }
+}
+
+/// Indented tree of the value, one element per line. Lists of scalars are written on one
+/// line, rows of a grid of chars as text:
+///
+/// ```text
+/// tuple (2)
+///   [0] section "seeds"
+///     [79, 14, 55, 13]
+///   [1] vec (7)
+///     [0] section "seed-to-soil"
+/// ```
+pub fn tree(value: &Value) -> String {
+    let mut out = String::new();
+    write_tree(value, "", 0, &mut out);
+    out
+}
+
+fn write_json(value: &Value, out: &mut String) {
+    let list = |values: &mut dyn Iterator<Item = &Value>, out: &mut String| {
+        out.push('[');
+        for (i, value) in values.enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json(value, out);
+        }
+        out.push(']');
+    };
+    let object = |fields: &mut dyn Iterator<Item = (&str, &Value)>, out: &mut String| {
+        out.push('{');
+        for (i, (name, value)) in fields.enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_string(name, out);
+            out.push(':');
+            write_json(value, out);
+        }
+        out.push('}');
+    };
+    match value {
+        Value::Vec(values) | Value::Tuple(values) => list(&mut values.iter(), out),
+        Value::Record(fields) => object(&mut fields.iter().map(|(n, v)| (&**n, v)), out),
+        Value::Section(title, content) => object(
+            &mut [
+                ("title", &Value::Str(title.clone())),
+                ("content", &**content),
+            ]
+            .into_iter(),
+            out,
+        ),
+        // Maps with string keys are objects, other maps lists of key value pairs
+        Value::Map(entries) if entries.keys().all(|k| k.as_str().is_some()) => {
+            let entries = sorted(entries, |(k, _)| k.as_str().unwrap().to_string());
+            object(
+                &mut entries.into_iter().map(|(k, v)| (k.as_str().unwrap(), v)),
+                out,
+            )
+        }
+        Value::Map(entries) => {
+            out.push('[');
+            for (i, (k, v)) in sorted(entries, |(k, _)| json(k)).into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                list(&mut [k, v].into_iter(), out);
+            }
+            out.push(']');
+        }
+        Value::Set(values) => list(&mut sorted(values, |v| json(v)).into_iter(), out),
+        Value::Grid(grid) => {
+            out.push('[');
+            for (i, row) in grid.rows().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                list(&mut row.iter(), out);
+            }
+            out.push(']');
+        }
+        Value::Range(start, end) | Value::RangeInclusive(start, end) => {
+            let inclusive = matches!(value, Value::RangeInclusive(..));
+            out.push_str("{\"start\":");
+            write_json(start, out);
+            out.push_str(",\"end\":");
+            write_json(end, out);
+            write!(out, ",\"inclusive\":{inclusive}}}").unwrap();
+        }
+        Value::Str(s) => write_string(s, out),
+        Value::Int(i) => write!(out, "{i}").unwrap(),
+        Value::UInt(u) => write!(out, "{u}").unwrap(),
+        // JSON has no infinite numbers
+        Value::Float(f) if f.is_finite() => write!(out, "{f}").unwrap(),
+        Value::Float(_) => out.push_str("null"),
+        Value::Char(c) => write_string(&c.to_string(), out),
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", u32::from(c)).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Compact JSON of the value. Tuples and grids are arrays, records objects, sections
+/// `{"title", "content"}` objects and ranges `{"start", "end", "inclusive"}` objects. Maps
+/// with string keys are objects, other maps arrays of `[key, value]` pairs.
+pub fn json(value: &Value) -> String {
+    let mut out = String::new();
+    write_json(value, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::parse_input;
+
+    #[test]
+    fn test_tree() {
+        let value = parse_input(
+            "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {t sp i {rl sp i}}}\n...",
+            "seeds: 79 14\n\na-to-b map:\n50 98 2\n52 50 48",
+        )
+        .unwrap();
+        assert_eq!(
+            tree(&value),
+            "tuple (2)\n  [0] section \"seeds\"\n    [79, 14]\n  [1] vec (1)\n    \
+             [0] section \"a-to-b\"\n      vec (2)\n        [0] (50, 98..100)\n        \
+             [1] (52, 50..98)\n"
+        );
+
+        let value = parse_input(
+            "g c\nnlnl\nhm nl ~=~ {str} {set , c}",
+            "#.\n.#\n\nb = x\na = y,z",
+        )
+        .unwrap();
+        assert_eq!(
+            tree(&value),
+            "tuple (2)\n  [0] grid 2x2\n    [0] #.\n    [1] .#\n  [1] map (2)\n    \
+             \"a\" => set (2)\n      - 'y'\n      - 'z'\n    \"b\" => set (1)\n      - 'x'\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let value = parse_input(
+            "v nl {tpl \"Game \" {id: u32} \": \" {draws: v \"; \" {t sp u32 {str}}}}",
+            "Game 1: 3 \"blue\"; 4 red",
+        )
+        .unwrap();
+        assert_eq!(
+            json(&value),
+            r#"[{"id":1,"draws":[[3,"\"blue\""],[4,"red"]]}]"#
+        );
+
+        let value =
+            parse_input("t | {ri - u8} {mm , = c f} {sec : c}", "2-4|a=1,a=0.5|x:y").unwrap();
+        assert_eq!(
+            json(&value),
+            r#"[{"start":2,"end":4,"inclusive":true},[["a",[1,0.5]]],{"title":"x","content":"y"}]"#
+        );
+    }
+}
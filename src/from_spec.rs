@@ -0,0 +1,441 @@
+use std::ops::Range;
+
+use crate::{
+    interpreter::{FromValue, Interpret, ParseInputError, Value},
+    ContainerType, Delimiter, InnerType, InputError, IntType, Specification, Table, TableKey,
+};
+
+/// Parsing of a type straight from (a part of) the input, implemented for the inner types of
+/// the spec DSL and derived for structs with `#[derive(FromSpec)]`:
+///
+/// ```ignore
+/// #[derive(FromSpec)]
+/// #[spec(prefix = "Game ", delimiter = ": ")]
+/// struct Game {
+///     id: i64,
+///     #[spec(delimiter = "; ")]
+///     draws: Vec<Draw>,
+/// }
+/// ```
+///
+/// Struct attributes are the literal `prefix` of the input and the `delimiter` between the
+/// fields, which defaults to `sp`. Field attributes are
+/// - `prefix` and `suffix`: literal text before and after the field, a suffix ends the field
+///   instead of the delimiter
+/// - `delimiter`: the field is a collection of `FromSpec` elements split at the delimiter
+/// - `inner`: the field is parsed with a spec, e.g. `inner = "v sp i"`
+///
+/// Delimiters are written like in specs, so `nl`, `sp` and escapes can be used.
+///
+/// Enums without fields are alternations of tokens, which default to the variant names:
+///
+/// ```ignore
+/// #[derive(FromSpec)]
+/// enum Color {
+///     #[spec(token = "red")]
+///     Red,
+///     #[spec(token = "green")]
+///     Green,
+/// }
+/// ```
+///
+/// They also convert from the values of a table named by them, e.g. `v sp [Color]` or
+/// `v sp [Color: "green", "red"]` in `parse_input!`, and from strings and chars of their
+/// tokens.
+///
+/// `&'a str` fields borrow from the input. A struct with a lifetime parameter implements
+/// `FromSpec<'a>` for its first lifetime, and strings of its `inner` specs are typed `&'a str`.
+pub trait FromSpec<'a>: Sized {
+    /// Parses the part `range` of the input, errors are located within the whole input
+    fn from_spec_range(input: &'a str, range: Range<usize>) -> Result<Self, ParseInputError>;
+
+    fn from_spec(input: &'a str) -> Result<Self, ParseInputError> {
+        Self::from_spec_range(input, 0..input.len())
+    }
+}
+
+/// Tokens of an enum deriving `FromSpec`, in the order of its variants. They are the
+/// alternatives of the tables named by the enum.
+pub trait Tokens {
+    const TOKENS: &'static [&'static str];
+}
+
+macro_rules! impl_from_spec_inner {
+    ($t:ty, $inner:expr) => {
+        impl<'a> FromSpec<'a> for $t {
+            fn from_spec_range(
+                input: &'a str,
+                range: Range<usize>,
+            ) -> Result<Self, ParseInputError> {
+                $inner
+                    .interpret_range(input, range)
+                    .map(FromValue::from_value)
+            }
+        }
+    };
+}
+
+impl_from_spec_inner!(i8, InnerType::Integer(IntType::I8));
+impl_from_spec_inner!(i16, InnerType::Integer(IntType::I16));
+impl_from_spec_inner!(i32, InnerType::Integer(IntType::I32));
+impl_from_spec_inner!(i64, InnerType::Integer(IntType::I64));
+impl_from_spec_inner!(i128, InnerType::Integer(IntType::I128));
+impl_from_spec_inner!(isize, InnerType::Integer(IntType::Isize));
+impl_from_spec_inner!(u8, InnerType::Integer(IntType::U8));
+impl_from_spec_inner!(u16, InnerType::Integer(IntType::U16));
+impl_from_spec_inner!(u32, InnerType::Integer(IntType::U32));
+impl_from_spec_inner!(u64, InnerType::Integer(IntType::U64));
+impl_from_spec_inner!(u128, InnerType::Integer(IntType::U128));
+impl_from_spec_inner!(usize, InnerType::Integer(IntType::Usize));
+impl_from_spec_inner!(f64, InnerType::Float);
+impl_from_spec_inner!(char, InnerType::Char);
+impl_from_spec_inner!(
+    String,
+    Specification {
+        delimiter: Delimiter::None,
+        container: ContainerType::String,
+    }
+);
+impl_from_spec_inner!(
+    &'a str,
+    Specification {
+        delimiter: Delimiter::None,
+        container: ContainerType::String,
+    }
+);
+
+// Building blocks of derived implementations, positions are byte offsets into the input
+
+/// Matches the literal at the start of the range, returns the position after it
+#[doc(hidden)]
+pub fn literal(
+    input: &str,
+    range: Range<usize>,
+    literal: &str,
+    spec: &str,
+) -> Result<usize, InputError> {
+    if input[range.clone()].starts_with(literal) {
+        Ok(range.start + literal.len())
+    } else {
+        let line_end = input[range.clone()]
+            .find('\n')
+            .map_or(range.end, |i| range.start + i);
+        Err(InputError::new(
+            format!("{literal:?}"),
+            spec,
+            input,
+            range.start,
+            line_end,
+        ))
+    }
+}
+
+/// Finds the end of a field that is terminated by `terminator`, an empty terminator ends the
+/// field at the end of the range
+#[doc(hidden)]
+pub fn field_end(
+    input: &str,
+    range: Range<usize>,
+    terminator: &str,
+    spec: &str,
+) -> Result<usize, InputError> {
+    if terminator.is_empty() {
+        return Ok(range.end);
+    }
+    input[range.clone()]
+        .find(terminator)
+        .map(|i| range.start + i)
+        .ok_or_else(|| {
+            let line_end = input[range.clone()]
+                .find('\n')
+                .map_or(range.end, |i| range.start + i);
+            InputError::new(
+                format!("delimiter {terminator:?}"),
+                spec,
+                input,
+                range.start,
+                line_end,
+            )
+        })
+}
+
+/// Checks that a struct consumed all of its input
+#[doc(hidden)]
+pub fn end(input: &str, range: Range<usize>, spec: &str) -> Result<(), InputError> {
+    if range.is_empty() {
+        Ok(())
+    } else {
+        Err(InputError::new(
+            "end of input",
+            spec,
+            input,
+            range.start,
+            range.end,
+        ))
+    }
+}
+
+/// Index of the token that is the whole range
+#[doc(hidden)]
+pub fn alternative(
+    input: &str,
+    range: Range<usize>,
+    tokens: &[&str],
+    spec: &str,
+) -> Result<usize, InputError> {
+    tokens
+        .iter()
+        .position(|token| *token == &input[range.clone()])
+        .ok_or_else(|| {
+            let tokens = tokens.iter().map(|token| format!("{token:?}"));
+            InputError::new(
+                format!("one of {}", tokens.collect::<Vec<_>>().join(", ")),
+                spec,
+                input,
+                range.start,
+                range.end,
+            )
+        })
+}
+
+/// Index of the alternative of a value, which is the token of a named table, a string or a char
+#[doc(hidden)]
+pub fn alternative_index(value: &Value, tokens: &[&str]) -> Option<usize> {
+    match value {
+        Value::Str(s) => tokens.iter().position(|token| token == s),
+        Value::Char(c) => tokens.iter().position(|token| *token == c.to_string()),
+        _ => None,
+    }
+}
+
+/// Sets the alternatives of the tables named by an enum to its tokens
+#[doc(hidden)]
+pub fn resolve_tables(tables: Vec<&mut Table>, name: &str, tokens: &[&str]) {
+    for table in tables {
+        if table.name.as_deref() == Some(name) {
+            table.entries = (0..)
+                .zip(tokens)
+                .map(|(i, token)| (TableKey::Str((*token).into()), i))
+                .collect();
+        }
+    }
+}
+
+const fn same_str(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether the alternatives of a table are the tokens of its enum in any order, checked at
+/// compile time by the macros
+#[doc(hidden)]
+pub const fn same_tokens(tokens: &[&str], alternatives: &[&str]) -> bool {
+    // Neither of them contains duplicates
+    if tokens.len() != alternatives.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < alternatives.len() {
+        let mut j = 0;
+        while j < tokens.len() && !same_str(tokens[j], alternatives[i]) {
+            j += 1;
+        }
+        if j == tokens.len() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Parses the elements of a collection field split at the delimiter
+#[doc(hidden)]
+pub fn elements<'a, T: FromSpec<'a>, C: FromIterator<T>>(
+    input: &'a str,
+    range: Range<usize>,
+    delimiter: &str,
+) -> Result<C, ParseInputError> {
+    let part = &input[range.clone()];
+    if part.is_empty() {
+        return Ok(std::iter::empty().collect());
+    }
+    let ranges: Vec<_> = if delimiter.is_empty() {
+        part.char_indices()
+            .map(|(i, c)| range.start + i..range.start + i + c.len_utf8())
+            .collect()
+    } else {
+        let mut start = range.start;
+        let mut ranges = vec![];
+        for (i, _) in part.match_indices(delimiter) {
+            ranges.push(start..range.start + i);
+            start = range.start + i + delimiter.len();
+        }
+        ranges.push(start..range.end);
+        ranges
+    };
+    ranges
+        .into_iter()
+        .map(|range| T::from_spec_range(input, range))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input_error<T: std::fmt::Debug>(result: Result<T, ParseInputError>) -> InputError {
+        match result {
+            Err(ParseInputError::Input(e)) => e,
+            result => panic!("expected input error, got {result:?}"),
+        }
+    }
+
+    #[derive(Debug, PartialEq, crate::FromSpec)]
+    #[spec(prefix = "Game ", delimiter = ": ")]
+    struct Game {
+        id: i64,
+        #[spec(delimiter = "; ")]
+        draws: Vec<Draw>,
+    }
+
+    #[derive(Debug, PartialEq, crate::FromSpec)]
+    struct Draw {
+        #[spec(inner = "v \", \" {t sp i {str}}")]
+        cubes: Vec<(i64, String)>,
+    }
+
+    #[derive(Debug, PartialEq, crate::FromSpec)]
+    #[spec(delimiter = "")]
+    struct Node {
+        #[spec(suffix = " = ")]
+        name: String,
+        #[spec(prefix = "(", suffix = ", ")]
+        left: String,
+        #[spec(suffix = ")")]
+        right: String,
+    }
+
+    #[derive(Debug, PartialEq, crate::FromSpec)]
+    #[spec(delimiter = " -> ")]
+    struct Module<'a> {
+        name: &'a str,
+        #[spec(inner = "v \", \" {str}")]
+        outputs: Vec<&'a str>,
+    }
+
+    #[derive(Debug, PartialEq, crate::FromSpec)]
+    enum Color {
+        #[spec(token = "red")]
+        Red,
+        #[spec(token = "blue")]
+        Blue,
+        Green,
+    }
+
+    #[derive(Debug, PartialEq, crate::FromSpec)]
+    struct Cubes {
+        count: u32,
+        color: Color,
+    }
+
+    #[test]
+    fn test_derive() {
+        let game = Game::from_spec("Game 3: 1 red, 2 blue; 4 green").unwrap();
+        assert_eq!(
+            game,
+            Game {
+                id: 3,
+                draws: vec![
+                    Draw {
+                        cubes: vec![(1, "red".into()), (2, "blue".into())]
+                    },
+                    Draw {
+                        cubes: vec![(4, "green".into())]
+                    },
+                ]
+            }
+        );
+
+        let node = Node::from_spec("AAA = (BBB, CCC)").unwrap();
+        assert_eq!(
+            (node.name.as_str(), node.left.as_str(), node.right.as_str()),
+            ("AAA", "BBB", "CCC")
+        );
+    }
+
+    #[test]
+    fn test_derive_borrowed() {
+        let input = String::from("broadcaster -> a, b, c");
+        let module = Module::from_spec(&input).unwrap();
+        assert_eq!(
+            module,
+            Module {
+                name: "broadcaster",
+                outputs: vec!["a", "b", "c"]
+            }
+        );
+        assert!(std::ptr::eq(module.name, &input[..11]));
+    }
+
+    #[test]
+    fn test_derive_enum() {
+        let cubes = Cubes::from_spec("3 blue").unwrap();
+        assert_eq!(
+            cubes,
+            Cubes {
+                count: 3,
+                color: Color::Blue
+            }
+        );
+        assert_eq!(Color::from_spec("Green").unwrap(), Color::Green);
+
+        let err = input_error(Cubes::from_spec("3 yellow"));
+        assert_eq!(
+            err.message(),
+            "expected one of \"red\", \"blue\", \"Green\", found \"yellow\""
+        );
+        assert_eq!((err.column, err.spec.as_str()), (2, "Color"));
+
+        // Tables are resolved by token, not by the position of the alternative
+        let colors =
+            crate::parse_input!("v sp [Color: \"blue\", \"Green\", \"red\"]", "blue red").unwrap();
+        assert_eq!(colors, vec![Color::Blue, Color::Red]);
+        let colors = crate::parse_input!("v sp [Color]", "Green red").unwrap();
+        assert_eq!(colors, vec![Color::Green, Color::Red]);
+        let parser = crate::nom_parser!("v sp [Color]");
+        let result: nom::IResult<&str, _> = parser("red Green");
+        assert_eq!(result, Ok(("", vec![Color::Red, Color::Green])));
+        let value = crate::interpreter::IntoValue::into_value(Color::Blue);
+        assert_eq!(value, Value::Str("blue".into()));
+        assert_eq!(Color::from_value(value), Color::Blue);
+        let colors: Vec<Color> = FromValue::from_value(
+            crate::interpreter::parse_input("v sp {str}", "Green blue").unwrap(),
+        );
+        assert_eq!(colors, vec![Color::Green, Color::Blue]);
+    }
+
+    #[test]
+    fn test_derive_errors() {
+        let err = input_error(Game::from_spec("Game x: 1 red"));
+        assert_eq!(err.message(), "expected integer, found \"x\"");
+        assert_eq!(err.column, 5);
+
+        let err = input_error(Game::from_spec("Gam 3: 1 red"));
+        assert_eq!(err.message(), "expected \"Game \", found \"Gam 3: 1 red\"");
+        assert_eq!(err.spec, "Game");
+
+        let err = input_error(Node::from_spec("AAA = (BBB, CCC)x"));
+        assert_eq!(err.message(), "expected end of input, found \"x\"");
+        assert_eq!(err.column, 16);
+    }
+}
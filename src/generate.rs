@@ -0,0 +1,384 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    ContainerType, Delimiter, InnerType, IntType, RangeKind, Specification, SpecificationList,
+    TemplatePart, Wildcard,
+};
+
+/// Bounds of the generated input. The text is only read back correctly if the alphabet
+/// doesn't contain the delimiters of the spec, e.g. no space for a `v sp {str}`.
+#[derive(Debug, Clone)]
+pub struct Constraints {
+    /// Integers and floats, narrowed to the range of the integer type of the spec
+    pub integers: RangeInclusive<i64>,
+    /// Number of elements of `v`, `hm` and `set`, chunks of `ch`, and length of `str` and wildcard text
+    pub lengths: RangeInclusive<usize>,
+    /// Chars of `c`, `str` and wildcard text
+    pub alphabet: Vec<char>,
+    /// Number of rows and columns of `g`
+    pub grid_size: (RangeInclusive<usize>, RangeInclusive<usize>),
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Constraints {
+            integers: -100..=100,
+            lengths: 1..=5,
+            alphabet: ('a'..='z').collect(),
+            grid_size: (1..=8, 1..=8),
+        }
+    }
+}
+
+fn int_bounds(ty: IntType) -> (i128, i128) {
+    match ty {
+        IntType::I8 => (i8::MIN.into(), i8::MAX.into()),
+        IntType::I16 => (i16::MIN.into(), i16::MAX.into()),
+        IntType::I32 => (i32::MIN.into(), i32::MAX.into()),
+        IntType::I64 | IntType::Isize => (i64::MIN.into(), i64::MAX.into()),
+        IntType::I128 => (i128::MIN, i128::MAX),
+        IntType::U8 => (0, u8::MAX.into()),
+        IntType::U16 => (0, u16::MAX.into()),
+        IntType::U32 => (0, u32::MAX.into()),
+        IntType::U64 | IntType::Usize => (0, u64::MAX.into()),
+        IntType::U128 => (0, i128::MAX),
+    }
+}
+
+/// Whether a value of the inner type of a `ch` is a whole chunk, which is the case for nested
+/// specs that are split at the delimiter of the chunks themselves. Other values are a single
+/// element of a chunk.
+fn is_whole_chunk(inner: &InnerType, delimiter: &Delimiter) -> bool {
+    match inner {
+        InnerType::Nested(spec) => delimiter.text().is_some() && spec.delimiter == *delimiter,
+        _ => false,
+    }
+}
+
+/// Random input text that follows a spec, from a seeded RNG so that failing inputs can be
+/// reproduced:
+///
+/// ```ignore
+/// let specs = parse_specs("v nl {v sp i}").unwrap();
+/// let mut generator = Generator::new(42, Constraints::default());
+/// for _ in 0..100 {
+///     let input = generator.generate(&specs);
+///     assert_eq!(solve(&input), solve_brute_force(&input));
+/// }
+/// ```
+pub struct Generator {
+    rng: StdRng,
+    constraints: Constraints,
+}
+
+impl Generator {
+    pub fn new(seed: u64, constraints: Constraints) -> Self {
+        Generator {
+            rng: StdRng::seed_from_u64(seed),
+            constraints,
+        }
+    }
+
+    /// Sections of the specs joined with the macro delimiters
+    pub fn generate(&mut self, specs: &SpecificationList) -> String {
+        let mut out = String::new();
+        for (i, spec) in specs.specifications.iter().enumerate() {
+            if i > 0 {
+                out.push_str(specs.macro_delimiters[i - 1].text().unwrap_or_default());
+            }
+            self.spec(spec, &mut out);
+        }
+        // The last section was generated once already
+        if specs.repeat_last {
+            let delimiter = specs.macro_delimiters.last().unwrap();
+            let last = specs.specifications.last().unwrap();
+            for _ in 1..self.length() {
+                out.push_str(delimiter.text().unwrap_or_default());
+                self.spec(last, &mut out);
+            }
+        }
+        out
+    }
+
+    fn length(&mut self) -> usize {
+        self.rng.gen_range(self.constraints.lengths.clone())
+    }
+
+    fn char(&mut self) -> char {
+        *self
+            .constraints
+            .alphabet
+            .choose(&mut self.rng)
+            .expect("Alphabet is empty")
+    }
+
+    fn word(&mut self, out: &mut String) {
+        for _ in 0..self.length().max(1) {
+            let c = self.char();
+            out.push(c);
+        }
+    }
+
+    fn int(&mut self, ty: IntType) -> i128 {
+        let (min, max) = int_bounds(ty);
+        let start = i128::from(*self.constraints.integers.start()).max(min);
+        let end = i128::from(*self.constraints.integers.end()).min(max);
+        assert!(
+            start <= end,
+            "No {} within {:?}",
+            ty.name(),
+            self.constraints.integers
+        );
+        self.rng.gen_range(start..=end)
+    }
+
+    fn wildcard(&mut self, wildcard: Wildcard, out: &mut String) {
+        match wildcard {
+            Wildcard::Char => {
+                let c = self.char();
+                out.push(c);
+            }
+            Wildcard::Chars => self.word(out),
+            Wildcard::Line => {
+                for i in 0..self.length() {
+                    if i > 0 {
+                        out.push(' ');
+                    }
+                    self.word(out);
+                }
+            }
+        }
+    }
+
+    fn inner(&mut self, inner: &InnerType, out: &mut String) {
+        match inner {
+            InnerType::Integer(ty) => {
+                let i = self.int(*ty);
+                out.push_str(&i.to_string());
+            }
+            InnerType::Float => {
+                let start = *self.constraints.integers.start() as f64;
+                let end = *self.constraints.integers.end() as f64;
+                out.push_str(&self.rng.gen_range(start..=end).to_string());
+            }
+            InnerType::Char => {
+                let c = self.char();
+                out.push(c);
+            }
+            InnerType::Nested(spec) => self.spec(spec, out),
+            InnerType::Wildcard(wildcard) => self.wildcard(*wildcard, out),
+            InnerType::Table(table) => {
+                let (key, _) = table.entries.choose(&mut self.rng).expect("Table is empty");
+                out.push_str(&key.text());
+            }
+        }
+    }
+
+    fn joined(&mut self, delimiter: &Delimiter, inner: &InnerType, count: usize, out: &mut String) {
+        for i in 0..count {
+            if i > 0 {
+                out.push_str(delimiter.text().unwrap_or_default());
+            }
+            self.inner(inner, out);
+        }
+    }
+
+    fn spec(&mut self, spec: &Specification, out: &mut String) {
+        match &spec.container {
+            ContainerType::Vec(inner) | ContainerType::HashSet(inner) => {
+                let count = self.length();
+                self.joined(&spec.delimiter, inner, count, out);
+            }
+            ContainerType::Array(len, inner) => self.joined(&spec.delimiter, inner, *len, out),
+            // Entries with a key that was already generated are dropped, except in multimaps
+            ContainerType::HashMap(separator, key, value)
+            | ContainerType::MultiMap(separator, key, value) => {
+                let is_multimap = matches!(spec.container, ContainerType::MultiMap(..));
+                let mut keys = HashSet::new();
+                let mut count = 0;
+                for _ in 0..self.length() {
+                    let mut k = String::new();
+                    self.inner(key, &mut k);
+                    if !keys.insert(k.clone()) && !is_multimap {
+                        continue;
+                    }
+                    if count > 0 {
+                        out.push_str(spec.delimiter.text().unwrap_or_default());
+                    }
+                    count += 1;
+                    out.push_str(&k);
+                    out.push_str(separator.text().unwrap_or_default());
+                    self.inner(value, out);
+                }
+            }
+            ContainerType::Tuple(inners) => {
+                for (i, inner) in inners.iter().enumerate() {
+                    self.inner(inner, out);
+                    if i + 1 < inners.len() {
+                        // `***` is terminated by its newline instead of the delimiter
+                        if let InnerType::Wildcard(Wildcard::Line) = inner {
+                            out.push('\n');
+                        } else {
+                            out.push_str(spec.delimiter.text().unwrap_or_default());
+                        }
+                    }
+                }
+            }
+            ContainerType::Grid(inner) => {
+                let rows = self.rng.gen_range(self.constraints.grid_size.0.clone());
+                let columns = self.rng.gen_range(self.constraints.grid_size.1.clone());
+                for i in 0..rows {
+                    if i > 0 {
+                        out.push('\n');
+                    }
+                    self.joined(&spec.delimiter, inner, columns, out);
+                }
+            }
+            ContainerType::Template(parts) => {
+                for part in parts {
+                    match part {
+                        TemplatePart::Literal(text) => out.push_str(text),
+                        TemplatePart::Placeholder(_, inner) => self.inner(inner, out),
+                    }
+                }
+            }
+            // A length is clamped so that the end stays within the type
+            ContainerType::Range(kind, ty) => {
+                let (a, b) = (self.int(*ty), self.int(*ty));
+                let (start, end) = (a.min(b), a.max(b));
+                let second = match kind {
+                    RangeKind::Length => (end - start).min(int_bounds(*ty).1),
+                    RangeKind::Exclusive | RangeKind::Inclusive => end,
+                };
+                let delimiter = spec.delimiter.text().unwrap_or_default();
+                out.push_str(&format!("{start}{delimiter}{second}"));
+            }
+            // An inner value that is a single element, like a `str`, is repeated to fill its
+            // chunk, others like `{rl sp i}` are a whole chunk
+            ContainerType::Chunks(len, inner) => {
+                let delimiter = spec.delimiter.text().unwrap_or_default();
+                let repeats = if is_whole_chunk(inner, &spec.delimiter) {
+                    1
+                } else {
+                    *len
+                };
+                for i in 0..self.length() * repeats {
+                    if i > 0 {
+                        out.push_str(delimiter);
+                    }
+                    self.inner(inner, out);
+                }
+            }
+            ContainerType::Section(inner) => {
+                self.word(out);
+                out.push_str(spec.delimiter.text().unwrap_or_default());
+                self.inner(inner, out);
+            }
+            ContainerType::String => self.word(out),
+            ContainerType::FixedString(len) => {
+                for _ in 0..*len {
+                    let c = self.char();
+                    out.push(c);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::{Interpret, Value};
+
+    #[test]
+    fn test_generated_inputs_parse() {
+        for specs in [
+            "v nl {v sp i}",
+            "t \": \" {str} {v sp i}\nnlnl\nv nlnl {t nl *** {v nl {v sp u32}}}",
+            "v nl {t \": \" ** {t sp * f c}}",
+            "hm \", \" c {set , i8}",
+            "g ['.', '#' = 5]\nnlnl\ng sp i",
+            "v nl {tpl {from} \" = (\" {left} \", \" {right} \")\"}",
+            "t \": \" ** {ch 2 sp {rl sp i8}}\nnlnl\nv nl {t sp i {ri - u8} {r .. i}}",
+            "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {v sp u32}}\n...",
+            "v nl {t sp {str5} {v3 , u8}}",
+            "ch 2 sp {str}",
+            "ch 2 , {t , i u8}",
+        ] {
+            let specs = crate::parse_specs(specs).unwrap();
+            let mut generator = Generator::new(1, Constraints::default());
+            for _ in 0..20 {
+                let input = generator.generate(&specs);
+                specs.interpret(&input).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_whole_chunks() {
+        for (spec, whole) in [
+            ("ch 2 sp {rl sp i}", true),
+            ("ch 2 , {t , i u8}", true),
+            ("ch 2 sp {str}", false),
+            ("ch 2 sp {v , i}", false),
+            ("ch 3 sp i", false),
+        ] {
+            let spec = spec.parse::<Specification>().unwrap();
+            let ContainerType::Chunks(_, inner) = &spec.container else {
+                unreachable!()
+            };
+            assert_eq!(is_whole_chunk(inner, &spec.delimiter), whole, "{spec}");
+        }
+
+        // Words are repeated even if they contain the delimiter of the chunks
+        let spec = "ch 2 - {str}".parse::<Specification>().unwrap();
+        let constraints = Constraints {
+            lengths: 1..=1,
+            alphabet: vec!['-'],
+            ..Constraints::default()
+        };
+        let mut out = String::new();
+        Generator::new(0, constraints).spec(&spec, &mut out);
+        assert_eq!(out, "---");
+    }
+
+    #[test]
+    fn test_seed_and_constraints() {
+        let specs = crate::parse_specs("v nl {v sp u8}").unwrap();
+        let constraints = Constraints {
+            integers: -5..=5,
+            lengths: 3..=3,
+            ..Constraints::default()
+        };
+        let input = Generator::new(7, constraints.clone()).generate(&specs);
+        assert_eq!(
+            Generator::new(7, constraints.clone()).generate(&specs),
+            input
+        );
+        assert_ne!(Generator::new(8, constraints).generate(&specs), input);
+
+        let Value::Vec(lines) = specs.interpret(&input).unwrap() else {
+            panic!("Expected lines")
+        };
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            let numbers = line.as_slice().unwrap();
+            assert_eq!(numbers.len(), 3);
+            assert!(numbers.iter().all(|n| n.as_uint().unwrap() <= 5));
+        }
+
+        let specs = crate::parse_specs("g c").unwrap();
+        let constraints = Constraints {
+            alphabet: vec!['.', '#'],
+            grid_size: (2..=2, 4..=4),
+            ..Constraints::default()
+        };
+        let input = Generator::new(0, constraints).generate(&specs);
+        assert_eq!(input.lines().count(), 2);
+        assert!(input
+            .lines()
+            .all(|l| l.len() == 4 && l.chars().all(|c| c == '.' || c == '#')));
+    }
+}
@@ -0,0 +1,78 @@
+use std::ops::{Index, IndexMut};
+
+/// Rectangular 2D grid stored row by row, indexed by `(row, column)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid of the given rows, `None` if they don't have the same width
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Option<Self> {
+        let mut rows = rows.into_iter().peekable();
+        let width = rows.peek().map_or(0, Vec::len);
+        let mut cells = vec![];
+        for row in rows {
+            if row.len() != width {
+                return None;
+            }
+            cells.extend(row);
+        }
+        Some(Grid { width, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Option<&T> {
+        (column < self.width)
+            .then(|| self.cells.get(row * self.width + column))
+            .flatten()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// All cells with their `(row, column)`
+    pub fn positions(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / width, i % width), cell))
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            cells: self.cells.into_iter().map(f).collect(),
+        }
+    }
+
+    pub fn into_cells(self) -> Vec<T> {
+        self.cells
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        assert!(column < self.width, "Column {column} out of bounds");
+        &self.cells[row * self.width + column]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        assert!(column < self.width, "Column {column} out of bounds");
+        &mut self.cells[row * self.width + column]
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashSet;
+
+use crate::{
+    ContainerType, Delimiter, InnerType, IntType, Specification, SpecificationList, Wildcard,
+};
+
+// Inference works on groups of samples that are expected to share a structure, e.g. all
+// lines of a block or all first fields of these lines. The printed spec of the result is
+// `to_string()`.
+
+/// Delimiters tried within a line, structural ones first
+const DELIMITERS: [&str; 8] = [": ", " | ", " = ", "; ", ", ", " ", ",", "-"];
+
+fn string() -> InnerType {
+    InnerType::Nested(Box::new(Specification {
+        delimiter: Delimiter::None,
+        container: ContainerType::String,
+    }))
+}
+
+fn delimiter(text: &str) -> Delimiter {
+    match text {
+        "\n" => Delimiter::NewLine,
+        "\n\n" => Delimiter::DoubleNewLine,
+        " " => Delimiter::Space,
+        _ => Delimiter::Custom(text.into()),
+    }
+}
+
+fn is_float(sample: &str) -> bool {
+    sample.contains('.')
+        && sample
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+        && sample.parse::<f64>().is_ok()
+}
+
+fn is_grid(lines: &[&str]) -> bool {
+    let width = lines[0].chars().count();
+    lines.len() > 1
+        && width > 1
+        && lines
+            .iter()
+            .all(|l| l.chars().count() == width && !l.contains(char::is_whitespace))
+        && lines.iter().any(|l| l.parse::<i64>().is_err())
+}
+
+/// Words over a tiny alphabet like `LRRL` or `?.##` are most likely sequences of symbols
+fn is_symbols(samples: &[&str]) -> bool {
+    let alphabet = samples
+        .iter()
+        .flat_map(|s| s.chars())
+        .collect::<HashSet<_>>();
+    let len = samples.iter().map(|s| s.chars().count()).sum::<usize>();
+    samples.iter().all(|s| s.chars().count() > 1)
+        && alphabet.len() <= 4
+        && len >= 2 * alphabet.len() * samples.len()
+        && !alphabet
+            .iter()
+            .any(|c| c.is_whitespace() || c.is_ascii_digit())
+}
+
+fn infer_inner(samples: &[&str]) -> InnerType {
+    if samples.iter().all(|s| s.parse::<i64>().is_ok()) {
+        InnerType::Integer(IntType::I64)
+    } else if samples
+        .iter()
+        .all(|s| is_float(s) || s.parse::<i64>().is_ok())
+    {
+        InnerType::Float
+    } else if samples.iter().all(|s| s.chars().count() == 1) {
+        InnerType::Char
+    } else {
+        match infer_spec(samples) {
+            Specification {
+                container: ContainerType::String,
+                ..
+            } => string(),
+            spec => InnerType::Nested(Box::new(spec)),
+        }
+    }
+}
+
+/// Spec of the lines of the samples, a grid if every sample is a rectangle of symbols
+fn lines_spec(samples: &[&str]) -> Specification {
+    if samples
+        .iter()
+        .all(|s| is_grid(&s.split('\n').collect::<Vec<_>>()))
+    {
+        return Specification {
+            delimiter: Delimiter::None,
+            container: ContainerType::Grid(InnerType::Char),
+        };
+    }
+    let lines = samples
+        .iter()
+        .flat_map(|s| s.split('\n'))
+        .collect::<Vec<_>>();
+    Specification {
+        delimiter: Delimiter::NewLine,
+        container: ContainerType::Vec(infer_inner(&lines)),
+    }
+}
+
+/// Spec of multi-line samples. If there are several samples, a first line that doesn't fit
+/// the others is taken as a header.
+fn infer_lines(samples: &[&str]) -> Specification {
+    let all = lines_spec(samples);
+    let split = samples
+        .iter()
+        .map(|s| s.split_once('\n'))
+        .collect::<Option<Vec<_>>>();
+    if let Some(split) = split.filter(|_| samples.len() > 1) {
+        let (headers, rest): (Vec<_>, Vec<_>) = split.into_iter().unzip();
+        if !rest.iter().any(|r| r.is_empty()) {
+            let body = lines_spec(&rest);
+            if body != all {
+                return Specification {
+                    delimiter: Delimiter::NewLine,
+                    container: ContainerType::Tuple(vec![
+                        infer_inner(&headers),
+                        InnerType::Nested(Box::new(body)),
+                    ]),
+                };
+            }
+        }
+    }
+    all
+}
+
+/// Tuple of the fields at each position, or `None` if the samples don't split into the
+/// same number of fields of different kinds
+fn infer_tuple(fields: &[Vec<&str>]) -> Option<Vec<InnerType>> {
+    let count = fields[0].len();
+    if count > 4 || fields.iter().any(|f| f.len() != count) {
+        return None;
+    }
+    let columns = (0..count)
+        .map(|i| fields.iter().map(|f| f[i]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let inners = columns.iter().map(|c| infer_inner(c)).collect::<Vec<_>>();
+    // Words repeated in every sample like `Game` or `map:` carry no information
+    let is_constant = |column: &Vec<&str>| {
+        column.len() > 1
+            && column.iter().all(|s| *s == column[0])
+            && column[0].parse::<f64>().is_err()
+    };
+    let wildcards = columns.iter().filter(|c| is_constant(c)).count();
+    if wildcards == 0 && inners.iter().all(|inner| *inner == inners[0]) {
+        return None;
+    }
+    if wildcards == count {
+        return Some(inners);
+    }
+    Some(
+        columns
+            .iter()
+            .zip(inners)
+            .map(|(column, inner)| {
+                if is_constant(column) {
+                    InnerType::Wildcard(Wildcard::Chars)
+                } else {
+                    inner
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Spec that matches all samples
+pub fn infer_spec(samples: &[&str]) -> Specification {
+    if samples.iter().any(|s| s.is_empty()) {
+        return Specification {
+            delimiter: Delimiter::None,
+            container: ContainerType::String,
+        };
+    }
+    if samples.iter().any(|s| s.contains('\n')) {
+        return infer_lines(samples);
+    }
+    for text in DELIMITERS {
+        let fields = samples
+            .iter()
+            .map(|s| s.split(text).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        if fields.iter().all(|f| f.len() < 2) || fields.iter().any(|f| f.contains(&"")) {
+            continue;
+        }
+        let container = match infer_tuple(&fields) {
+            Some(inners) => ContainerType::Tuple(inners),
+            None => ContainerType::Vec(infer_inner(&fields.concat())),
+        };
+        return Specification {
+            delimiter: delimiter(text),
+            container,
+        };
+    }
+    if is_symbols(samples) {
+        Specification {
+            delimiter: Delimiter::None,
+            container: ContainerType::Vec(InnerType::Char),
+        }
+    } else {
+        Specification {
+            delimiter: Delimiter::None,
+            container: ContainerType::String,
+        }
+    }
+}
+
+/// Proposes specs for a sample input. Blank line separated blocks of the same structure
+/// become a `v nlnl` spec, a first block that differs from the others gets its own section.
+pub fn infer_specs(sample: &str) -> SpecificationList {
+    let sample = sample.trim_end_matches('\n');
+    let blocks = sample.split("\n\n").collect::<Vec<_>>();
+    let repeated = |blocks: &[&str]| match infer_inner(blocks) {
+        InnerType::Nested(spec) if blocks.len() == 1 => *spec,
+        inner => Specification {
+            delimiter: Delimiter::DoubleNewLine,
+            container: ContainerType::Vec(inner),
+        },
+    };
+    if blocks.len() == 1 {
+        return SpecificationList {
+            specifications: vec![infer_spec(&blocks)],
+            macro_delimiters: vec![],
+            repeat_last: false,
+        };
+    }
+    let all = repeated(&blocks);
+    if blocks.len() > 2 && all == repeated(&blocks[1..]) {
+        return SpecificationList {
+            specifications: vec![all],
+            macro_delimiters: vec![],
+            repeat_last: false,
+        };
+    }
+    SpecificationList {
+        specifications: vec![infer_spec(&blocks[..1]), repeated(&blocks[1..])],
+        macro_delimiters: vec![Delimiter::DoubleNewLine],
+        repeat_last: false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::Interpret;
+
+    #[test]
+    fn test_infer_lines() {
+        let specs = infer_specs("Game 1: 3 blue, 4 red; 1 red\nGame 2: 1 blue\n");
+        assert_eq!(
+            specs.to_string(),
+            "v nl {t \": \" {t sp ** i} {v \"; \" {v \", \" {t sp i {str}}}}}"
+        );
+        assert_eq!(infer_specs("1 2 3\n4 5\n").to_string(), "v nl {v sp i}");
+        assert_eq!(infer_specs("#..\n.#.\n").to_string(), "g c");
+    }
+
+    #[test]
+    fn test_infer_real_inputs() {
+        for (day, expected) in [
+            (
+                5,
+                "t \": \" {str} {v sp i}\nnlnl\n\
+                 v nlnl {t nl {t sp {t \"-\" {str} ** {str}} **} {v nl {v sp i}}}",
+            ),
+            (8, "v c\nnlnl\nv nl {t \" = \" {str} {v \", \" {str}}}"),
+            (11, "g c"),
+        ] {
+            let input = std::fs::read_to_string(format!("in/{day}r.txt")).unwrap();
+            let specs = infer_specs(&input);
+            assert_eq!(specs.to_string(), expected);
+            specs.interpret(&input).unwrap();
+        }
+
+        for entry in std::fs::read_dir("in").unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+            let input = std::fs::read_to_string(path).unwrap();
+            infer_specs(&input).interpret(&input).unwrap();
+        }
+    }
+}
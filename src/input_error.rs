@@ -0,0 +1,88 @@
+use std::{fmt::Display, path::Path};
+
+/// Puzzle input that doesn't match its spec, pointing at the offending part of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputError {
+    /// What the spec expected at this position, e.g. "integer"
+    pub expected: String,
+    /// The input found instead, quoted
+    pub found: String,
+    /// The spec node that was being matched, in spec syntax
+    pub spec: String,
+    /// Line within the input, starting at 1
+    pub line: usize,
+    /// Column within the line in chars, starting at 0
+    pub column: usize,
+    /// Number of chars the error spans, at least 1
+    pub len: usize,
+    /// Text of the offending input line
+    pub source_line: String,
+}
+
+impl InputError {
+    /// Locates an error by the byte range `start..end` of `input`. Only the part of the range
+    /// on its first line is underlined.
+    pub(crate) fn new(
+        expected: impl Into<String>,
+        spec: impl Into<String>,
+        input: &str,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        let before = &input[..start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let source_line = input[line_start..].split('\n').next().unwrap_or_default();
+        let part = &input[start..end];
+        let first_line = part.split('\n').next().unwrap_or_default();
+        let found = if part.is_empty() {
+            "nothing".into()
+        } else if first_line.len() < part.len() {
+            format!("{first_line:?}…")
+        } else {
+            format!("{part:?}")
+        };
+        InputError {
+            expected: expected.into(),
+            found,
+            spec: spec.into(),
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..start].chars().count(),
+            len: first_line.chars().count().max(1),
+            source_line: source_line.trim_end_matches('\r').into(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        format!("expected {}, found {}", self.expected, self.found)
+    }
+
+    /// The location and the input line with the error underlined, as printed by rustc
+    pub fn snippet(&self) -> String {
+        let line = self.line.to_string();
+        let pad = " ".repeat(line.len());
+        format!(
+            "{pad}--> input:{}:{}\n{pad} |\n{line} | {}\n{pad} | {}{}\n{pad} = note: while matching `{}`",
+            self.line,
+            self.column + 1,
+            self.source_line,
+            " ".repeat(self.column),
+            "^".repeat(self.len),
+            self.spec
+        )
+    }
+
+    /// The error as printed by `Display`, located within the file instead of the input string
+    pub fn in_file(&self, path: &Path) -> String {
+        let location = format!("--> {}:", path.display());
+        let snippet = self.snippet().replacen("--> input:", &location, 1);
+        format!("error: {}\n{snippet}", self.message())
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: {}\n{}", self.message(), self.snippet())
+    }
+}
+
+impl std::error::Error for InputError {}
@@ -0,0 +1,1664 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::{Hash, Hasher},
+    num::IntErrorKind,
+    ops::{Range, RangeInclusive},
+    sync::Arc,
+};
+
+use crate::{
+    grid::Grid, ContainerType, Delimiter, InnerType, InputError, IntType, RangeKind, SpecError,
+    Specification, SpecificationList, TemplatePart, Wildcard,
+};
+
+/// Dynamically typed result of applying a specification to some input. Strings are borrowed
+/// from the input, they are only owned in values built from typed data by `IntoValue`.
+#[derive(Debug, Clone)]
+pub enum Value<'a> {
+    Vec(Vec<Value<'a>>),
+    Tuple(Vec<Value<'a>>),
+    /// Named fields of a template in their order, the names are shared with the spec
+    Record(Vec<(Arc<str>, Value<'a>)>),
+    /// Title and content of a named section
+    Section(Cow<'a, str>, Box<Value<'a>>),
+    Map(HashMap<Value<'a>, Value<'a>>),
+    Set(HashSet<Value<'a>>),
+    Grid(Grid<Value<'a>>),
+    /// Start and exclusive end
+    Range(Box<Value<'a>>, Box<Value<'a>>),
+    /// Start and inclusive end
+    RangeInclusive(Box<Value<'a>>, Box<Value<'a>>),
+    Str(Cow<'a, str>),
+    /// Integers of the signed types
+    Int(i128),
+    /// Integers of the unsigned types
+    UInt(u128),
+    Float(f64),
+    Char(char),
+}
+
+// Floats are compared bitwise so that values can be used as map keys
+impl PartialEq for Value<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Vec(l), Value::Vec(r)) | (Value::Tuple(l), Value::Tuple(r)) => l == r,
+            (Value::Record(l), Value::Record(r)) => l == r,
+            (Value::Section(l, l_value), Value::Section(r, r_value)) => {
+                l == r && l_value == r_value
+            }
+            (Value::Map(l), Value::Map(r)) => l == r,
+            (Value::Set(l), Value::Set(r)) => l == r,
+            (Value::Grid(l), Value::Grid(r)) => l == r,
+            (Value::Range(l, l_end), Value::Range(r, r_end))
+            | (Value::RangeInclusive(l, l_end), Value::RangeInclusive(r, r_end)) => {
+                l == r && l_end == r_end
+            }
+            (Value::Str(l), Value::Str(r)) => l == r,
+            (Value::Int(l), Value::Int(r)) => l == r,
+            (Value::UInt(l), Value::UInt(r)) => l == r,
+            (Value::Float(l), Value::Float(r)) => l.to_bits() == r.to_bits(),
+            (Value::Char(l), Value::Char(r)) => l == r,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value<'_> {}
+
+impl Hash for Value<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::Vec(v) | Value::Tuple(v) => v.hash(state),
+            Value::Record(r) => r.hash(state),
+            Value::Section(title, value) => {
+                title.hash(state);
+                value.hash(state);
+            }
+            // Maps and sets have no defined order, their length is consistent with Eq
+            Value::Map(m) => m.len().hash(state),
+            Value::Set(s) => s.len().hash(state),
+            Value::Grid(g) => g.hash(state),
+            Value::Range(start, end) | Value::RangeInclusive(start, end) => {
+                start.hash(state);
+                end.hash(state);
+            }
+            Value::Str(s) => s.hash(state),
+            Value::Int(i) => i.hash(state),
+            Value::UInt(u) => u.hash(state),
+            Value::Float(f) => f.to_bits().hash(state),
+            Value::Char(c) => c.hash(state),
+        }
+    }
+}
+
+impl<'a> Value<'a> {
+    pub fn as_slice(&self) -> Option<&[Value<'a>]> {
+        match self {
+            Value::Vec(v) | Value::Tuple(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_record(&self) -> Option<&[(Arc<str>, Value<'a>)]> {
+        match self {
+            Value::Record(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    /// Field of a record by name
+    pub fn field(&self, name: &str) -> Option<&Value<'a>> {
+        self.as_record()?
+            .iter()
+            .find_map(|(field, value)| (**field == *name).then_some(value))
+    }
+
+    /// Title and content of a section
+    pub fn as_section(&self) -> Option<(&str, &Value<'a>)> {
+        match self {
+            Value::Section(title, value) => Some((title, value)),
+            _ => None,
+        }
+    }
+
+    /// Content of the first section with the title among the elements, including the
+    /// sections of a repeated last specification, which are collected in a list
+    pub fn section(&self, title: &str) -> Option<&Value<'a>> {
+        self.as_slice()?
+            .iter()
+            .flat_map(|element| match element {
+                Value::Vec(repeated) => repeated.as_slice(),
+                _ => std::slice::from_ref(element),
+            })
+            .find_map(|element| match element.as_section() {
+                Some((t, content)) if t == title => Some(content),
+                _ => None,
+            })
+    }
+
+    pub fn as_map(&self) -> Option<&HashMap<Value<'a>, Value<'a>>> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    pub fn as_set(&self) -> Option<&HashSet<Value<'a>>> {
+        match self {
+            Value::Set(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_grid(&self) -> Option<&Grid<Value<'a>>> {
+        match self {
+            Value::Grid(g) => Some(g),
+            _ => None,
+        }
+    }
+
+    /// Start and end of a range, and whether the end is inclusive
+    pub fn as_range(&self) -> Option<(&Value<'a>, &Value<'a>, bool)> {
+        match self {
+            Value::Range(start, end) => Some((start, end, false)),
+            Value::RangeInclusive(start, end) => Some((start, end, true)),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_uint(&self) -> Option<u128> {
+        match self {
+            Value::UInt(u) => Some(*u),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn as_char(&self) -> Option<char> {
+        match self {
+            Value::Char(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    /// Copy of the value that doesn't borrow from the input
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Vec(v) => Value::Vec(v.into_iter().map(Value::into_owned).collect()),
+            Value::Tuple(v) => Value::Tuple(v.into_iter().map(Value::into_owned).collect()),
+            Value::Record(r) => Value::Record(
+                r.into_iter()
+                    .map(|(name, v)| (name, v.into_owned()))
+                    .collect(),
+            ),
+            Value::Section(title, value) => {
+                Value::Section(Cow::Owned(title.into_owned()), Box::new(value.into_owned()))
+            }
+            Value::Map(m) => Value::Map(
+                m.into_iter()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect(),
+            ),
+            Value::Set(s) => Value::Set(s.into_iter().map(Value::into_owned).collect()),
+            Value::Grid(g) => Value::Grid(g.map(Value::into_owned)),
+            Value::Range(start, end) => {
+                Value::Range(Box::new(start.into_owned()), Box::new(end.into_owned()))
+            }
+            Value::RangeInclusive(start, end) => {
+                Value::RangeInclusive(Box::new(start.into_owned()), Box::new(end.into_owned()))
+            }
+            Value::Str(s) => Value::Str(Cow::Owned(s.into_owned())),
+            Value::Int(i) => Value::Int(i),
+            Value::UInt(u) => Value::UInt(u),
+            Value::Float(f) => Value::Float(f),
+            Value::Char(c) => Value::Char(c),
+        }
+    }
+}
+
+/// Input that doesn't match its spec, located by a byte range of the whole input. The
+/// innermost spec around the mismatch is filled in while the error propagates.
+struct Mismatch {
+    expected: String,
+    start: usize,
+    end: usize,
+    spec: Option<String>,
+    /// The spec itself is invalid, which only specs built by hand can be
+    invalid_spec: bool,
+}
+
+impl Mismatch {
+    fn new(expected: impl Into<String>, start: usize, end: usize) -> Self {
+        Mismatch {
+            expected: expected.into(),
+            start,
+            end,
+            spec: None,
+            invalid_spec: false,
+        }
+    }
+
+    /// A part of the spec that `parse_specs` rejects, reported as a spec error
+    fn invalid_spec(message: impl Into<String>, spec: &impl Display) -> Self {
+        Mismatch {
+            invalid_spec: true,
+            ..Mismatch::new(message, 0, 0).within(spec)
+        }
+    }
+
+    fn within(mut self, spec: &impl Display) -> Self {
+        self.spec.get_or_insert_with(|| spec.to_string());
+        self
+    }
+
+    fn locate(self, spec: &impl Display, input: &str) -> ParseInputError {
+        let spec = self.spec.unwrap_or_else(|| spec.to_string());
+        if self.invalid_spec {
+            return ParseInputError::Spec(SpecError {
+                message: self.expected,
+                line: 1,
+                column: 0,
+                len: spec.chars().count().max(1),
+                source_line: spec,
+            });
+        }
+        ParseInputError::Input(InputError::new(
+            self.expected,
+            spec,
+            input,
+            self.start,
+            self.end,
+        ))
+    }
+}
+
+/// Splits the input at the delimiter, returns the elements with their byte offsets. Runs of
+/// a repeated delimiter at the start and end of the input don't separate elements.
+fn split<'a>(delimiter: &Delimiter, input: &'a str) -> Vec<(usize, &'a str)> {
+    if input.is_empty() {
+        return vec![];
+    }
+    match delimiter.text() {
+        Some(_) => {
+            let ignore_runs = matches!(delimiter, Delimiter::Repeated(_));
+            let mut start = 0;
+            let mut elements = vec![];
+            while let Some(found) = delimiter.find(&input[start..]) {
+                if !(ignore_runs && start == 0 && found.start == 0) {
+                    elements.push((start, &input[start..start + found.start]));
+                }
+                start += found.end;
+            }
+            if !(ignore_runs && start == input.len()) {
+                elements.push((start, &input[start..]));
+            }
+            elements
+        }
+        None => input
+            .char_indices()
+            .map(|(i, c)| (i, &input[i..i + c.len_utf8()]))
+            .collect(),
+    }
+}
+
+/// Length of the next tuple field at the start of the input and of the delimiter after it,
+/// `None` if the delimiter is missing
+fn next_field(
+    delimiter: &Delimiter,
+    inner: &InnerType,
+    input: &str,
+    is_last: bool,
+) -> Option<(usize, usize)> {
+    if is_last {
+        return Some((input.len(), 0));
+    }
+    if let InnerType::Wildcard(Wildcard::Line) = inner {
+        return Some(input.find('\n').map_or((input.len(), 0), |i| (i, 1)));
+    }
+    match delimiter.text() {
+        Some(_) => delimiter
+            .find(input)
+            .map(|found| (found.start, found.len())),
+        None => Some((input.chars().next().map_or(0, char::len_utf8), 0)),
+    }
+}
+
+fn match_wildcard(wildcard: Wildcard, field: &str, offset: usize) -> Result<(), Mismatch> {
+    let (matches, expected) = match wildcard {
+        Wildcard::Char => (field.chars().count() == 1, "a single char"),
+        Wildcard::Chars | Wildcard::Line => (!field.contains('\n'), "no line break"),
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(Mismatch::new(
+            format!("{expected} for `{wildcard}`"),
+            offset,
+            offset + field.len(),
+        ))
+    }
+}
+
+macro_rules! parse_int {
+    ($input:expr, $t:ty, $variant:ident) => {
+        $input
+            .parse::<$t>()
+            .map(|i| Value::$variant(i as _))
+            .map_err(|e| {
+                // Negative numbers are out of range rather than invalid for unsigned types
+                let is_negative = $input
+                    .strip_prefix('-')
+                    .is_some_and(|digits| digits.parse::<u128>().is_ok());
+                match e.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => true,
+                    _ => is_negative,
+                }
+                .then(|| format!("{} between {} and {}", stringify!($t), <$t>::MIN, <$t>::MAX))
+                .unwrap_or_else(|| "integer".into())
+            })
+    };
+}
+
+/// Parses an integer of the given type, the error is what was expected instead
+pub(crate) fn parse_int(ty: IntType, input: &str) -> Result<Value<'static>, String> {
+    match ty {
+        IntType::I8 => parse_int!(input, i8, Int),
+        IntType::I16 => parse_int!(input, i16, Int),
+        IntType::I32 => parse_int!(input, i32, Int),
+        IntType::I64 => parse_int!(input, i64, Int),
+        IntType::I128 => parse_int!(input, i128, Int),
+        IntType::Isize => parse_int!(input, isize, Int),
+        IntType::U8 => parse_int!(input, u8, UInt),
+        IntType::U16 => parse_int!(input, u16, UInt),
+        IntType::U32 => parse_int!(input, u32, UInt),
+        IntType::U64 => parse_int!(input, u64, UInt),
+        IntType::U128 => parse_int!(input, u128, UInt),
+        IntType::Usize => parse_int!(input, usize, UInt),
+    }
+}
+
+fn interpret_inner<'a>(
+    inner: &InnerType,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let mismatch = |expected: &str| Mismatch::new(expected, offset, offset + input.len());
+    match inner {
+        InnerType::Integer(ty) => parse_int(*ty, input).map_err(|expected| mismatch(&expected)),
+        InnerType::Float => input
+            .parse()
+            .map(Value::Float)
+            .map_err(|_| mismatch("float")),
+        InnerType::Char => {
+            let mut chars = input.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Value::Char(c)),
+                _ => Err(mismatch("a single char")),
+            }
+        }
+        // Enums are resolved from the token of their variant
+        InnerType::Table(table) => table
+            .entries
+            .iter()
+            .find(|(key, _)| key.text() == input)
+            .map(|(_, v)| match table.name {
+                Some(_) => Value::Str(Cow::Borrowed(input)),
+                None => Value::Int((*v).into()),
+            })
+            .ok_or_else(|| {
+                let keys = table.entries.iter().map(|(key, _)| key.to_string());
+                mismatch(&format!("one of {}", keys.collect::<Vec<_>>().join(", ")))
+            }),
+        InnerType::Nested(spec) => interpret_spec(spec, input, offset),
+        InnerType::Wildcard(wildcard) => Err(Mismatch::invalid_spec(
+            "wildcards are only allowed as tuple fields",
+            wildcard,
+        )),
+    }
+}
+
+fn interpret_elements<'a, T: FromIterator<Value<'a>>>(
+    spec: &Specification,
+    inner: &InnerType,
+    input: &'a str,
+    offset: usize,
+) -> Result<T, Mismatch> {
+    split(&spec.delimiter, input)
+        .into_iter()
+        .map(|(i, element)| interpret_inner(inner, element, offset + i))
+        .collect()
+}
+
+/// Elements of a `Vec` that has to have a fixed length
+fn interpret_array<'a>(
+    spec: &Specification,
+    len: usize,
+    inner: &InnerType,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let values: Vec<_> = interpret_elements(spec, inner, input, offset)?;
+    if values.len() != len {
+        return Err(Mismatch::new(
+            format!("{len} elements"),
+            offset,
+            offset + input.len(),
+        ));
+    }
+    Ok(Value::Vec(values))
+}
+
+fn interpret_tuple<'a>(
+    spec: &Specification,
+    inners: &[InnerType],
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let mut position = 0;
+    let mut fields = vec![];
+    for (i, inner) in inners.iter().enumerate() {
+        let rest = &input[position..];
+        let is_last = i + 1 == inners.len();
+        let Some((len, delimiter_len)) = next_field(&spec.delimiter, inner, rest, is_last) else {
+            let line_len = rest.find('\n').unwrap_or(rest.len());
+            return Err(Mismatch::new(
+                format!(
+                    "tuple delimiter {:?}",
+                    spec.delimiter.text().unwrap_or_default()
+                ),
+                offset + position,
+                offset + position + line_len,
+            ));
+        };
+        let field = &rest[..len];
+        match inner {
+            InnerType::Wildcard(wildcard) => match_wildcard(*wildcard, field, offset + position)?,
+            _ => fields.push(interpret_inner(inner, field, offset + position)?),
+        }
+        position += len + delimiter_len;
+    }
+    Ok(if fields.len() == 1 {
+        fields.pop().unwrap()
+    } else {
+        Value::Tuple(fields)
+    })
+}
+
+/// Rows are lines, which have to consist of the same number of cells
+fn interpret_grid<'a>(
+    spec: &Specification,
+    inner: &InnerType,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let mut rows = vec![];
+    for (i, row) in split(&Delimiter::NewLine, input) {
+        let cells = split(&spec.delimiter, row)
+            .into_iter()
+            .map(|(j, cell)| interpret_inner(inner, cell, offset + i + j))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(first) = rows.first().map(Vec::len) {
+            if cells.len() != first {
+                return Err(Mismatch::new(
+                    format!(
+                        "row of {first} cells like the first row, not {}",
+                        cells.len()
+                    ),
+                    offset + i,
+                    offset + i + row.len(),
+                ));
+            }
+        }
+        rows.push(cells);
+    }
+    Ok(Value::Grid(
+        Grid::from_rows(rows).expect("rows are checked to have the same width"),
+    ))
+}
+
+/// End of a range given by its start and length, `None` if it doesn't fit the type
+pub(crate) fn length_end(ty: IntType, start: &Value, length: &Value) -> Option<Value<'static>> {
+    match (start, length) {
+        (Value::Int(s), Value::Int(l)) => s.checked_add(*l).map(|e| e.to_string()),
+        (Value::UInt(s), Value::UInt(l)) => s.checked_add(*l).map(|e| e.to_string()),
+        _ => None,
+    }
+    .and_then(|end| parse_int(ty, &end).ok())
+}
+
+/// The numbers are separated by the first delimiter after the sign of the start
+fn interpret_range<'a>(
+    spec: &Specification,
+    kind: RangeKind,
+    ty: IntType,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let Some(text) = spec.delimiter.text() else {
+        return Err(Mismatch::invalid_spec(
+            "ranges need a delimiter between their numbers",
+            spec,
+        ));
+    };
+    let sign = usize::from(input.starts_with('-'));
+    let Some(found) = spec.delimiter.find(&input[sign..]) else {
+        return Err(Mismatch::new(
+            format!("range delimiter {text:?}"),
+            offset,
+            offset + input.len(),
+        ));
+    };
+    let number = |start: usize, end: usize| {
+        parse_int(ty, &input[start..end])
+            .map_err(|expected| Mismatch::new(expected, offset + start, offset + end))
+    };
+    let (i, j) = (sign + found.start, sign + found.end);
+    let start = number(0, i)?;
+    let second = number(j, input.len())?;
+    Ok(match kind {
+        RangeKind::Exclusive => Value::Range(Box::new(start), Box::new(second)),
+        RangeKind::Inclusive => Value::RangeInclusive(Box::new(start), Box::new(second)),
+        RangeKind::Length => {
+            let end = length_end(ty, &start, &second).ok_or_else(|| {
+                Mismatch::new(
+                    format!("length with an end within {}", ty.name()),
+                    offset + j,
+                    offset + input.len(),
+                )
+            })?;
+            Value::Range(Box::new(start), Box::new(end))
+        }
+    })
+}
+
+/// The title ends at the first delimiter, the rest of the input is the content
+fn interpret_section<'a>(
+    spec: &Specification,
+    inner: &InnerType,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let Some(found) = spec.delimiter.find(input) else {
+        let end = input.find('\n').unwrap_or(input.len());
+        let text = spec.delimiter.text().unwrap_or_default();
+        return Err(Mismatch::new(
+            format!("{text:?} after section title"),
+            offset,
+            offset + end,
+        ));
+    };
+    let content = interpret_inner(inner, &input[found.end..], offset + found.end)?;
+    Ok(Value::Section(
+        Cow::Borrowed(&input[..found.start]),
+        Box::new(content),
+    ))
+}
+
+/// Each chunk is the text from its first to its last element, including the delimiters
+fn interpret_chunks<'a>(
+    spec: &Specification,
+    len: usize,
+    inner: &InnerType,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let elements = split(&spec.delimiter, input);
+    let rest = elements.len() % len;
+    if rest > 0 {
+        let (start, _) = elements[elements.len() - rest];
+        return Err(Mismatch::new(
+            format!("{len} elements per chunk, not {rest} in the last one"),
+            offset + start,
+            offset + input.len(),
+        ));
+    }
+    elements
+        .chunks(len)
+        .map(|chunk| {
+            let (start, _) = chunk[0];
+            let (last, element) = chunk[len - 1];
+            interpret_inner(inner, &input[start..last + element.len()], offset + start)
+        })
+        .collect::<Result<_, _>>()
+        .map(Value::Vec)
+}
+
+/// Key and value of an entry are separated by the first separator. A key that occurs twice
+/// is an error, unless the values are collected in a multimap.
+fn interpret_map<'a>(
+    spec: &Specification,
+    separator: &Delimiter,
+    key: &InnerType,
+    value: &InnerType,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let is_multimap = matches!(spec.container, ContainerType::MultiMap(..));
+    let mut entries = HashMap::new();
+    for (i, entry) in split(&spec.delimiter, input) {
+        let offset = offset + i;
+        let Some(found) = separator.find(entry) else {
+            let expected = match separator {
+                Delimiter::Space => "space".into(),
+                _ => format!("{:?}", separator.text().unwrap_or_default()),
+            };
+            return Err(Mismatch::new(
+                format!("{expected} between key and value"),
+                offset,
+                offset + entry.len(),
+            ));
+        };
+        let k = interpret_inner(key, &entry[..found.start], offset)?;
+        let v = interpret_inner(value, &entry[found.end..], offset + found.end)?;
+        if is_multimap {
+            let Value::Vec(values) = entries.entry(k).or_insert_with(|| Value::Vec(vec![])) else {
+                unreachable!("values of a multimap are lists");
+            };
+            values.push(v);
+        } else if entries.insert(k, v).is_some() {
+            return Err(Mismatch::new(
+                "key that occurs once",
+                offset,
+                offset + found.start,
+            ));
+        }
+    }
+    Ok(Value::Map(entries))
+}
+
+/// Each placeholder ends at the literal text following it
+fn interpret_template<'a>(
+    parts: &[TemplatePart],
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let line_end = |position: usize| {
+        offset
+            + input[position..]
+                .find('\n')
+                .map_or(input.len(), |i| position + i)
+    };
+    let mut position = 0;
+    let mut fields = vec![];
+    for (i, part) in parts.iter().enumerate() {
+        match part {
+            TemplatePart::Literal(text) => {
+                if !input[position..].starts_with(&**text) {
+                    return Err(Mismatch::new(
+                        format!("{text:?}"),
+                        offset + position,
+                        line_end(position),
+                    ));
+                }
+                position += text.len();
+            }
+            TemplatePart::Placeholder(name, inner) => {
+                let end = match parts.get(i + 1) {
+                    Some(TemplatePart::Literal(text)) => input[position..]
+                        .find(&**text)
+                        .map(|j| position + j)
+                        .ok_or_else(|| {
+                            Mismatch::new(
+                                format!("{text:?}"),
+                                offset + position,
+                                line_end(position),
+                            )
+                        })?,
+                    _ => input.len(),
+                };
+                let value = interpret_inner(inner, &input[position..end], offset + position)?;
+                fields.push((Arc::clone(name), value));
+                position = end;
+            }
+        }
+    }
+    if position < input.len() {
+        return Err(Mismatch::new(
+            "end of template",
+            offset + position,
+            offset + input.len(),
+        ));
+    }
+    Ok(Value::Record(fields))
+}
+
+fn interpret_spec<'a>(
+    spec: &Specification,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let value = match &spec.container {
+        ContainerType::Vec(inner) => interpret_elements(spec, inner, input, offset).map(Value::Vec),
+        ContainerType::Array(len, inner) => interpret_array(spec, *len, inner, input, offset),
+        ContainerType::HashSet(inner) => {
+            interpret_elements(spec, inner, input, offset).map(Value::Set)
+        }
+        ContainerType::Tuple(inners) => interpret_tuple(spec, inners, input, offset),
+        ContainerType::Grid(inner) => interpret_grid(spec, inner, input, offset),
+        ContainerType::Template(parts) => interpret_template(parts, input, offset),
+        ContainerType::Range(kind, ty) => interpret_range(spec, *kind, *ty, input, offset),
+        ContainerType::Chunks(len, inner) => interpret_chunks(spec, *len, inner, input, offset),
+        ContainerType::Section(inner) => interpret_section(spec, inner, input, offset),
+        ContainerType::HashMap(separator, key, value)
+        | ContainerType::MultiMap(separator, key, value) => {
+            interpret_map(spec, separator, key, value, input, offset)
+        }
+        ContainerType::String => Ok(Value::Str(Cow::Borrowed(input))),
+        ContainerType::FixedString(len) if input.chars().count() == *len => {
+            Ok(Value::Str(Cow::Borrowed(input)))
+        }
+        ContainerType::FixedString(len) => Err(Mismatch::new(
+            format!("{len} chars"),
+            offset,
+            offset + input.len(),
+        )),
+    };
+    value.map_err(|m| m.within(spec))
+}
+
+fn interpret_list<'a>(
+    specs: &SpecificationList,
+    input: &'a str,
+    offset: usize,
+) -> Result<Value<'a>, Mismatch> {
+    let input = input.trim_end_matches('\n');
+    let Some(last) = specs.specifications.last() else {
+        return Err(Mismatch::invalid_spec(
+            "specification list needs at least one specification",
+            &"",
+        ));
+    };
+    if specs.macro_delimiters.len() + 1 != specs.specifications.len() {
+        return Err(Mismatch::invalid_spec(
+            "every specification but the last needs a macro delimiter",
+            specs,
+        ));
+    }
+    let mut position = 0;
+    let mut sections = vec![];
+    for (spec, delimiter) in specs.specifications.iter().zip(&specs.macro_delimiters) {
+        // Sections can't be told apart without text between them
+        let Some(text) = delimiter.text() else {
+            return Err(Mismatch::invalid_spec(
+                "macro delimiters need text between the sections",
+                delimiter,
+            ));
+        };
+        // A missing section is reported at the end of the input
+        let Some(found) = delimiter.find(&input[position..]) else {
+            let end = offset + input.len();
+            return Err(
+                Mismatch::new(format!("section delimiter {text:?}"), end, end).within(spec),
+            );
+        };
+        sections.push(interpret_spec(
+            spec,
+            &input[position..position + found.start],
+            offset + position,
+        )?);
+        position += found.end;
+    }
+    sections.push(if specs.repeat_last {
+        let Some(delimiter) = specs.macro_delimiters.last() else {
+            return Err(Mismatch::invalid_spec(
+                "`...` repeats the last specification at a macro delimiter, there is none",
+                specs,
+            ));
+        };
+        split(delimiter, &input[position..])
+            .into_iter()
+            .map(|(i, section)| interpret_spec(last, section, offset + position + i))
+            .collect::<Result<_, _>>()
+            .map(Value::Vec)?
+    } else {
+        interpret_spec(last, &input[position..], offset + position)?
+    });
+
+    Ok(if sections.len() == 1 {
+        sections.pop().unwrap()
+    } else {
+        Value::Tuple(sections)
+    })
+}
+
+/// Application of a (part of a) specification to some input
+pub trait Interpret {
+    /// Interprets the part `range` of the input, errors are located within the whole input.
+    /// A spec built by hand that `parse_specs` would reject is reported as a spec error.
+    fn interpret_range<'a>(
+        &self,
+        input: &'a str,
+        range: Range<usize>,
+    ) -> Result<Value<'a>, ParseInputError>;
+
+    fn interpret<'a>(&self, input: &'a str) -> Result<Value<'a>, ParseInputError> {
+        self.interpret_range(input, 0..input.len())
+    }
+}
+
+impl Interpret for InnerType {
+    fn interpret_range<'a>(
+        &self,
+        input: &'a str,
+        range: Range<usize>,
+    ) -> Result<Value<'a>, ParseInputError> {
+        interpret_inner(self, &input[range.clone()], range.start).map_err(|m| m.locate(self, input))
+    }
+}
+
+impl Interpret for Specification {
+    fn interpret_range<'a>(
+        &self,
+        input: &'a str,
+        range: Range<usize>,
+    ) -> Result<Value<'a>, ParseInputError> {
+        interpret_spec(self, &input[range.clone()], range.start).map_err(|m| m.locate(self, input))
+    }
+}
+
+/// Splits the input at the macro delimiters and interprets each section with its
+/// specification. Multiple sections are returned as a tuple, the sections of a repeated
+/// last specification as a list.
+impl Interpret for SpecificationList {
+    fn interpret_range<'a>(
+        &self,
+        input: &'a str,
+        range: Range<usize>,
+    ) -> Result<Value<'a>, ParseInputError> {
+        interpret_list(self, &input[range.clone()], range.start).map_err(|m| m.locate(self, input))
+    }
+}
+
+/// Conversion of an interpreted value into the type derived from its specification,
+/// used by the `parse_input!` macro. `&'a str` borrows strings from the input.
+pub trait FromValue<'a>: Sized {
+    fn from_value(value: Value<'a>) -> Self;
+}
+
+macro_rules! impl_from_value_int {
+    ($($t:ty),+) => {
+        $(
+            impl<'a> FromValue<'a> for $t {
+                fn from_value(value: Value<'a>) -> Self {
+                    let int = match value {
+                        Value::Int(i) => <$t>::try_from(i).ok(),
+                        Value::UInt(u) => <$t>::try_from(u).ok(),
+                        _ => None,
+                    };
+                    int.unwrap_or_else(|| panic!("Expected {}, got {value:?}", stringify!($t)))
+                }
+            }
+        )+
+    };
+}
+
+impl_from_value_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<'a> FromValue<'a> for f64 {
+    fn from_value(value: Value<'a>) -> Self {
+        value
+            .as_float()
+            .unwrap_or_else(|| panic!("Expected float, got {value:?}"))
+    }
+}
+
+impl<'a> FromValue<'a> for char {
+    fn from_value(value: Value<'a>) -> Self {
+        value
+            .as_char()
+            .unwrap_or_else(|| panic!("Expected char, got {value:?}"))
+    }
+}
+
+impl<'a> FromValue<'a> for String {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::Str(s) => s.into_owned(),
+            _ => panic!("Expected string, got {value:?}"),
+        }
+    }
+}
+
+impl<'a> FromValue<'a> for &'a str {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::Str(Cow::Borrowed(s)) => s,
+            _ => panic!("Expected string borrowed from the input, got {value:?}"),
+        }
+    }
+}
+
+impl<'a, T: FromValue<'a>> FromValue<'a> for Vec<T> {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::Vec(v) => v.into_iter().map(T::from_value).collect(),
+            _ => panic!("Expected vec, got {value:?}"),
+        }
+    }
+}
+
+/// Fixed-length strings are arrays of their chars
+impl<'a, T: FromValue<'a>, const N: usize> FromValue<'a> for [T; N] {
+    fn from_value(value: Value<'a>) -> Self {
+        let elements = match value {
+            Value::Vec(v) => v.into_iter().map(T::from_value).collect::<Vec<_>>(),
+            Value::Str(s) => s.chars().map(|c| T::from_value(Value::Char(c))).collect(),
+            _ => panic!("Expected array, got {value:?}"),
+        };
+        let len = elements.len();
+        elements
+            .try_into()
+            .unwrap_or_else(|_| panic!("Expected {N} elements, got {len}"))
+    }
+}
+
+impl<'a, T: FromValue<'a> + Hash + Eq> FromValue<'a> for HashSet<T> {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::Set(s) => s.into_iter().map(T::from_value).collect(),
+            _ => panic!("Expected set, got {value:?}"),
+        }
+    }
+}
+
+impl<'a, K: FromValue<'a> + Hash + Eq, V: FromValue<'a>> FromValue<'a> for HashMap<K, V> {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::Map(m) => m
+                .into_iter()
+                .map(|(k, v)| (K::from_value(k), V::from_value(v)))
+                .collect(),
+            _ => panic!("Expected map, got {value:?}"),
+        }
+    }
+}
+
+impl<'a, T: FromValue<'a>> FromValue<'a> for Grid<T> {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::Grid(g) => g.map(T::from_value),
+            _ => panic!("Expected grid, got {value:?}"),
+        }
+    }
+}
+
+impl<'a, T: FromValue<'a>> FromValue<'a> for Range<T> {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::Range(start, end) => T::from_value(*start)..T::from_value(*end),
+            _ => panic!("Expected range, got {value:?}"),
+        }
+    }
+}
+
+impl<'a, T: FromValue<'a>> FromValue<'a> for RangeInclusive<T> {
+    fn from_value(value: Value<'a>) -> Self {
+        match value {
+            Value::RangeInclusive(start, end) => T::from_value(*start)..=T::from_value(*end),
+            _ => panic!("Expected inclusive range, got {value:?}"),
+        }
+    }
+}
+
+macro_rules! impl_from_value_tuple {
+    ($($t:ident),+) => {
+        impl<'a, $($t: FromValue<'a>),+> FromValue<'a> for ($($t,)+) {
+            fn from_value(value: Value<'a>) -> Self {
+                let fields = match value {
+                    Value::Tuple(fields) => fields,
+                    // Templates are typed as tuples of their fields
+                    Value::Record(fields) => fields.into_iter().map(|(_, v)| v).collect(),
+                    // Sections are typed as title and content
+                    Value::Section(title, value) => vec![Value::Str(title), *value],
+                    _ => panic!("Expected tuple, got {value:?}"),
+                };
+                let mut fields = fields.into_iter();
+                let tuple = ($($t::from_value(
+                    fields.next().expect("Too few tuple fields"),
+                ),)+);
+                assert!(fields.next().is_none(), "Too many tuple fields");
+                tuple
+            }
+        }
+    };
+}
+
+impl_from_value_tuple!(A);
+impl_from_value_tuple!(A, B);
+impl_from_value_tuple!(A, B, C);
+impl_from_value_tuple!(A, B, C, D);
+impl_from_value_tuple!(A, B, C, D, E);
+impl_from_value_tuple!(A, B, C, D, E, F);
+impl_from_value_tuple!(A, B, C, D, E, F, G);
+impl_from_value_tuple!(A, B, C, D, E, F, G, H);
+
+/// Inverse of `FromValue`, used to render typed values with a spec
+pub trait IntoValue<'a> {
+    fn into_value(self) -> Value<'a>;
+}
+
+macro_rules! impl_into_value_int {
+    ($variant:ident: $($t:ty),+) => {
+        $(
+            impl<'a> IntoValue<'a> for $t {
+                fn into_value(self) -> Value<'a> {
+                    Value::$variant(self as _)
+                }
+            }
+        )+
+    };
+}
+
+impl_into_value_int!(Int: i8, i16, i32, i64, i128, isize);
+impl_into_value_int!(UInt: u8, u16, u32, u64, u128, usize);
+
+impl<'a> IntoValue<'a> for f64 {
+    fn into_value(self) -> Value<'a> {
+        Value::Float(self)
+    }
+}
+
+impl<'a> IntoValue<'a> for char {
+    fn into_value(self) -> Value<'a> {
+        Value::Char(self)
+    }
+}
+
+impl<'a> IntoValue<'a> for String {
+    fn into_value(self) -> Value<'a> {
+        Value::Str(Cow::Owned(self))
+    }
+}
+
+impl<'a> IntoValue<'a> for &'a str {
+    fn into_value(self) -> Value<'a> {
+        Value::Str(Cow::Borrowed(self))
+    }
+}
+
+impl<'a, T: IntoValue<'a>> IntoValue<'a> for Vec<T> {
+    fn into_value(self) -> Value<'a> {
+        Value::Vec(self.into_iter().map(T::into_value).collect())
+    }
+}
+
+impl<'a, T: IntoValue<'a>, const N: usize> IntoValue<'a> for [T; N] {
+    fn into_value(self) -> Value<'a> {
+        Value::Vec(self.into_iter().map(T::into_value).collect())
+    }
+}
+
+impl<'a, T: IntoValue<'a>> IntoValue<'a> for HashSet<T> {
+    fn into_value(self) -> Value<'a> {
+        Value::Set(self.into_iter().map(T::into_value).collect())
+    }
+}
+
+impl<'a, K: IntoValue<'a>, V: IntoValue<'a>> IntoValue<'a> for HashMap<K, V> {
+    fn into_value(self) -> Value<'a> {
+        Value::Map(
+            self.into_iter()
+                .map(|(k, v)| (k.into_value(), v.into_value()))
+                .collect(),
+        )
+    }
+}
+
+impl<'a, T: IntoValue<'a>> IntoValue<'a> for Grid<T> {
+    fn into_value(self) -> Value<'a> {
+        Value::Grid(self.map(T::into_value))
+    }
+}
+
+impl<'a, T: IntoValue<'a>> IntoValue<'a> for Range<T> {
+    fn into_value(self) -> Value<'a> {
+        Value::Range(
+            Box::new(self.start.into_value()),
+            Box::new(self.end.into_value()),
+        )
+    }
+}
+
+impl<'a, T: IntoValue<'a>> IntoValue<'a> for RangeInclusive<T> {
+    fn into_value(self) -> Value<'a> {
+        let (start, end) = self.into_inner();
+        Value::RangeInclusive(Box::new(start.into_value()), Box::new(end.into_value()))
+    }
+}
+
+macro_rules! impl_into_value_tuple {
+    ($($t:ident),+) => {
+        impl<'a, $($t: IntoValue<'a>),+> IntoValue<'a> for ($($t,)+) {
+            #[allow(non_snake_case)]
+            fn into_value(self) -> Value<'a> {
+                let ($($t,)+) = self;
+                Value::Tuple(vec![$($t.into_value()),+])
+            }
+        }
+    };
+}
+
+impl_into_value_tuple!(A);
+impl_into_value_tuple!(A, B);
+impl_into_value_tuple!(A, B, C);
+impl_into_value_tuple!(A, B, C, D);
+impl_into_value_tuple!(A, B, C, D, E);
+impl_into_value_tuple!(A, B, C, D, E, F);
+impl_into_value_tuple!(A, B, C, D, E, F, G);
+impl_into_value_tuple!(A, B, C, D, E, F, G, H);
+
+/// Failure of `parse_input` and `Interpret`, either an invalid spec or input that doesn't
+/// match it
+#[derive(Debug)]
+pub enum ParseInputError {
+    Spec(SpecError),
+    Input(InputError),
+}
+
+impl Display for ParseInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseInputError::Spec(e) => write!(f, "{e}"),
+            ParseInputError::Input(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseInputError {}
+
+impl From<SpecError> for ParseInputError {
+    fn from(e: SpecError) -> Self {
+        ParseInputError::Spec(e)
+    }
+}
+
+impl From<InputError> for ParseInputError {
+    fn from(e: InputError) -> Self {
+        ParseInputError::Input(e)
+    }
+}
+
+/// Interprets the input with specs given at runtime, see `parse_input!` for specs checked at
+/// compile time
+pub fn parse_input<'a>(specs: &str, input: &'a str) -> Result<Value<'a>, ParseInputError> {
+    crate::parse_specs(specs)?.interpret(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Error of input that doesn't match a valid spec
+    fn mismatch(specs: &str, input: &str) -> InputError {
+        match parse_input(specs, input) {
+            Err(ParseInputError::Input(e)) => e,
+            result => panic!("expected input error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_vec_vec_int() {
+        assert_eq!(
+            parse_input("v nl {v sp i}", "1 2 3\n-4 5 6\n").unwrap(),
+            Value::Vec(vec![
+                Value::Vec(vec![Value::Int(1), Value::Int(2), Value::Int(3)]),
+                Value::Vec(vec![Value::Int(-4), Value::Int(5), Value::Int(6)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let value = parse_input(
+            "t \": \" {str} {v sp i}\nnlnl\nv nl {v c}",
+            "seeds: 79 14\n\n#.\n.#\n",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            Value::Tuple(vec![
+                Value::Tuple(vec![
+                    Value::Str("seeds".into()),
+                    Value::Vec(vec![Value::Int(79), Value::Int(14)]),
+                ]),
+                Value::Vec(vec![
+                    Value::Vec(vec![Value::Char('#'), Value::Char('.')]),
+                    Value::Vec(vec![Value::Char('.'), Value::Char('#')]),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_named_sections() {
+        let specs = "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {v sp i}}\n...";
+        let input = "seeds: 79 14\n\na-to-b map:\n1 2 3\n\nb-to-c map:\n4 5 6\n7 8 9\n";
+        let value = parse_input(specs, input).unwrap();
+        assert_eq!(
+            value.section("seeds"),
+            Some(&Value::Vec(vec![Value::Int(79), Value::Int(14)]))
+        );
+        let maps = value.as_slice().unwrap()[1].as_slice().unwrap();
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[1].as_section().unwrap().0, "b-to-c");
+        assert_eq!(
+            value.section("b-to-c").unwrap().as_slice().unwrap().len(),
+            2
+        );
+        assert_eq!(value.section("c-to-d"), None);
+
+        let (seeds, maps) = crate::parse_input!(
+            "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {v sp i}}\n...",
+            input
+        )
+        .unwrap();
+        assert_eq!(seeds, ("seeds".to_string(), vec![79, 14]));
+        assert_eq!(maps[0], ("a-to-b".to_string(), vec![vec![1, 2, 3]]));
+
+        let err = mismatch(specs, "seeds: 1\n\na-to-b\n1 2 3");
+        assert_eq!(
+            (err.line, err.message()),
+            (
+                3,
+                "expected \" map:\\n\" after section title, found \"a-to-b\"".into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_map_and_set() {
+        let value = parse_input("hm \", \" c {set , f}", "a 1.5,2, b 0.25").unwrap();
+        let map = value.as_map().unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(
+            map[&Value::Char('a')],
+            Value::Set(HashSet::from([Value::Float(1.5), Value::Float(2.0)]))
+        );
+
+        let nodes = crate::parse_input!(
+            ref "hm nl \" = \" {str} {tpl \"(\" {left} \", \" {right} \")\"}",
+            "AAA = (BBB, CCC)\nBBB = (AAA, ZZZ)"
+        )
+        .unwrap();
+        assert_eq!(nodes["BBB"], ("AAA", "ZZZ"));
+
+        let err = mismatch("hm nl ~=~ {str} i", "a = 1\nb = 2\na = 3");
+        assert_eq!((err.line, err.column), (3, 0));
+        assert_eq!(err.message(), "expected key that occurs once, found \"a\"");
+        let err = mismatch("hm , -> c i", "a->1,b 2");
+        assert_eq!(
+            err.message(),
+            "expected \"->\" between key and value, found \"b 2\""
+        );
+
+        let map = crate::parse_input!("mm ~,~ ~->~ c i", "a -> 1, b -> 2, a -> 3").unwrap();
+        assert_eq!(map, HashMap::from([('a', vec![1, 3]), ('b', vec![2])]));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert_eq!(
+            parse_input("v nl {t \": \" ** {v sp i}}", "Card 1: 41 48\nCard 2: 13\n").unwrap(),
+            Value::Vec(vec![
+                Value::Vec(vec![Value::Int(41), Value::Int(48)]),
+                Value::Vec(vec![Value::Int(13)]),
+            ])
+        );
+        assert_eq!(
+            parse_input(
+                "v nlnl {t nl *** {v nl {t * i c}}}",
+                "a-to-b map:\n#1x\n#2y\n\nb-to-c map:\n#3z\n"
+            )
+            .unwrap(),
+            Value::Vec(vec![
+                Value::Vec(vec![
+                    Value::Tuple(vec![Value::Int(1), Value::Char('x')]),
+                    Value::Tuple(vec![Value::Int(2), Value::Char('y')]),
+                ]),
+                Value::Vec(vec![Value::Tuple(vec![Value::Int(3), Value::Char('z')])]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_input_macro() {
+        let lines = crate::parse_input!("v nl {v sp i}", "1 2\n3 4\n").unwrap();
+        assert_eq!(lines, vec![vec![1, 2], vec![3, 4]]);
+
+        let (seeds, maps) = crate::parse_input!(
+            "t \": \" {str} {v sp i}\nnlnl\nv nlnl {hm nl c f}",
+            "seeds: 79 14\n\na 1.5\nb 2\n\nc 0\n"
+        )
+        .unwrap();
+        assert_eq!(seeds, ("seeds".to_string(), vec![79, 14]));
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[0][&'b'], 2.0);
+
+        let cards = crate::parse_input!("v nl {t \": \" ** {v sp i}}", "Card 1: 41 48\n").unwrap();
+        assert_eq!(cards, vec![vec![41, 48]]);
+    }
+
+    #[test]
+    fn test_borrowed_strings() {
+        let input = String::from("AAA = (BBB, CCC)\nBBB = (DDD, EEE)");
+        let nodes = crate::parse_input!(
+            ref "v nl {tpl {from} \" = (\" {left} \", \" {right} \")\"}",
+            &input
+        )
+        .unwrap();
+        assert_eq!(nodes[1], ("BBB", "DDD", "EEE"));
+        assert!(std::ptr::eq(nodes[0].0, &input[..3]));
+
+        let words: HashMap<&str, i64> =
+            FromValue::from_value(parse_input("hm , {str} i", "a 1,b 2").unwrap());
+        assert_eq!(words["b"], 2);
+    }
+
+    #[test]
+    fn test_template() {
+        let game = parse_input(
+            "tpl \"Game \" {id: u32} \": \" {draws: v \"; \" {v \", \" {t sp u32 {str}}}}",
+            "Game 3: 1 red, 2 blue; 4 green",
+        )
+        .unwrap();
+        assert_eq!(game.field("id"), Some(&Value::UInt(3)));
+        assert_eq!(game.field("draws").unwrap().as_slice().unwrap().len(), 2);
+
+        let nodes = crate::parse_input!(
+            "v nl {tpl {from} \" = (\" {left} \", \" {right} \")\"}",
+            "AAA = (BBB, CCC)\nBBB = (DDD, EEE)\n"
+        )
+        .unwrap();
+        assert_eq!(nodes[1], ("BBB".into(), "DDD".into(), "EEE".into()));
+
+        // The field names of every record are the ones of the spec
+        let specs = crate::parse_specs("v nl {tpl {from} \" = \" {to}}").unwrap();
+        let ContainerType::Vec(InnerType::Nested(spec)) = &specs.specifications[0].container else {
+            unreachable!()
+        };
+        let ContainerType::Template(parts) = &spec.container else {
+            unreachable!()
+        };
+        let TemplatePart::Placeholder(from, _) = &parts[0] else {
+            unreachable!()
+        };
+        let records = specs.interpret("a = b\nc = d").unwrap();
+        for record in records.as_slice().unwrap() {
+            assert!(Arc::ptr_eq(&record.as_record().unwrap()[0].0, from));
+        }
+
+        let err = mismatch("tpl {a: i} \" = (\" {b} \")\"", "1 = 2");
+        assert_eq!(err.message(), "expected \" = (\", found \"1 = 2\"");
+        assert_eq!(err.column, 0);
+        let err = mismatch("tpl \"#\" {a: i} \"!\"", "#1!x");
+        assert_eq!(err.expected, "end of template");
+        assert_eq!(err.column, 3);
+    }
+
+    #[test]
+    fn test_int_types() {
+        assert_eq!(
+            parse_input("t sp u8 i8 usize", "255 -128 7").unwrap(),
+            Value::Tuple(vec![Value::UInt(255), Value::Int(-128), Value::UInt(7)])
+        );
+        let (a, b) = crate::parse_input!(
+            "t sp u128 {v , i16}",
+            "340282366920938463463374607431768211455 -1,2"
+        )
+        .unwrap();
+        assert_eq!((a, b), (u128::MAX, vec![-1i16, 2]));
+
+        let err = mismatch("v nl {v sp u8}", "1 2\n3 256");
+        assert_eq!(
+            err.message(),
+            "expected u8 between 0 and 255, found \"256\""
+        );
+        assert_eq!((err.line, err.column, err.len), (2, 2, 3));
+        let err = mismatch("v sp u32", "1 -2");
+        assert_eq!(err.expected, "u32 between 0 and 4294967295");
+        let err = mismatch("v sp i8", "1 x");
+        assert_eq!(err.expected, "integer");
+    }
+
+    #[test]
+    fn test_grid() {
+        let value = parse_input("g ['.', '#']", "#..\n.#.\n").unwrap();
+        let grid = value.as_grid().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], Value::Int(1));
+        assert_eq!(grid[(1, 2)], Value::Int(0));
+
+        let grid = crate::parse_input!("g sp i", "1 2\n3 4").unwrap();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2], [3, 4]]);
+
+        let err = mismatch("g c", "#..\n.#.\n..\n...");
+        assert_eq!(
+            err.message(),
+            "expected row of 3 cells like the first row, not 2, found \"..\""
+        );
+        assert_eq!((err.line, err.column, err.len), (3, 0, 2));
+
+        let err = mismatch("g ['.', '#']", "#..\n.x.");
+        assert_eq!(err.expected, "one of '.', '#'");
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn test_ranges_and_chunks() {
+        let (seeds, maps) = crate::parse_input!(
+            "t \": \" ** {ch 2 sp {rl sp i}}\nnlnl\nv nl {t sp i {rl sp i}}",
+            "seeds: 79 14 55 13\n\n50 98 2\n52 50 48"
+        )
+        .unwrap();
+        assert_eq!(seeds, vec![79..93, 55..68]);
+        assert_eq!(maps, vec![(50, 98..100), (52, 50..98)]);
+
+        let pairs = crate::parse_input!("v nl {t , {ri - u8} {ri - u8}}", "2-4,6-8").unwrap();
+        assert_eq!(pairs, vec![(2..=4, 6..=8)]);
+        let range = crate::parse_input!("r .. i", "-3..-1").unwrap();
+        assert_eq!(range, -3..-1);
+        let range = crate::parse_input!("ri - i", "-3--1").unwrap();
+        assert_eq!(range, -3..=-1);
+
+        let err = mismatch("ch 2 sp i", "1 2 3");
+        assert_eq!(
+            err.message(),
+            "expected 2 elements per chunk, not 1 in the last one, found \"3\""
+        );
+        let err = mismatch("rl sp u8", "200 100");
+        assert_eq!(err.expected, "length with an end within u8");
+        assert_eq!(err.column, 4);
+        let err = mismatch("r .. i", "3-5");
+        assert_eq!(err.expected, "range delimiter \"..\"");
+    }
+
+    #[test]
+    fn test_fixed_length() {
+        let hands = crate::parse_input!("v nl {t sp {str5} u32}", "32T3K 765\nT55J5 684").unwrap();
+        assert_eq!(hands[1], (['T', '5', '5', 'J', '5'], 684));
+        let rows = crate::parse_input!("v nl {v3 sp i}", "50 98 2\n52 50 48").unwrap();
+        assert_eq!(rows, vec![[50, 98, 2], [52, 50, 48]]);
+        let (a, b) = crate::parse_input!("t | {v2 , u8} {str3}", "1,2|abc").unwrap();
+        assert_eq!((a, b), ([1, 2], ['a', 'b', 'c']));
+
+        let err = mismatch("v nl {v3 sp i}", "50 98 2\n52 50");
+        assert_eq!(
+            (err.line, err.column, err.message()),
+            (2, 0, "expected 3 elements, found \"52 50\"".into())
+        );
+        let err = mismatch("v nl {t sp {str5} u32}", "32T3K 765\nT55J 684");
+        assert_eq!(
+            (err.line, err.message()),
+            (2, "expected 5 chars, found \"T55J\"".into())
+        );
+    }
+
+    #[test]
+    fn test_alternations() {
+        let value = parse_input("v \", \" [\"red\", \"green\" = 5, 'b']", "green, b").unwrap();
+        assert_eq!(value, Value::Vec(vec![Value::Int(5), Value::Int(2)]));
+
+        let err = mismatch("v sp [\"red\", \"green\"]", "red gren");
+        assert_eq!(
+            err.message(),
+            "expected one of \"red\", \"green\", found \"gren\""
+        );
+        assert_eq!(err.column, 4);
+
+        let hands = crate::parse_input!("v nl {v ['2', '3', 'T', 'J', \"10\"]}", "23T\nJ").unwrap();
+        assert_eq!(hands, vec![vec![0, 1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_flexible_delimiters() {
+        let cards = crate::parse_input!(
+            "v nl {t ~:~ ** {t ~|~ {v sp+ u32} {v sp+ u32}}}",
+            "Card   1: 41  8 | 83 86  6\nCard  12:  1 48 |  9 83"
+        )
+        .unwrap();
+        assert_eq!(
+            cards,
+            vec![(vec![41, 8], vec![83, 86, 6]), (vec![1, 48], vec![9, 83])]
+        );
+
+        let value = parse_input("v ~,~ i", "1 ,2,  3").unwrap();
+        assert_eq!(value.as_slice().unwrap().len(), 3);
+        let rows = crate::parse_input!("g sp+ u8", "  1  2\n 10 20 \n").unwrap();
+        assert_eq!(rows.get(1, 0), Some(&10));
+        let fields = crate::parse_input!("t ,+ i i", "1,,,2").unwrap();
+        assert_eq!(fields, (1, 2));
+        let range = crate::parse_input!("r ~..~ i", "-3 .. 7").unwrap();
+        assert_eq!(range, -3..7);
+
+        let err = mismatch("v ~,~ i", "1 , x");
+        assert_eq!(
+            (err.column, err.message()),
+            (4, "expected integer, found \"x\"".into())
+        );
+    }
+
+    #[test]
+    fn test_located_mismatches() {
+        let err = mismatch("v nl {v sp i}", "1 2 3\n4 x 6\n");
+        assert_eq!(
+            err.to_string(),
+            "error: expected integer, found \"x\"\n \
+             --> input:2:3\n  |\n2 | 4 x 6\n  |   ^\n  = note: while matching `v sp i`"
+        );
+
+        let err = mismatch("v nl {t \": \" ** {v sp i}}", "Card 1: 4\nCard 2 5\n");
+        assert_eq!(
+            err.message(),
+            "expected tuple delimiter \": \", found \"Card 2 5\""
+        );
+        assert_eq!((err.line, err.column, err.len), (2, 0, 8));
+        assert_eq!(err.spec, "t \": \" ** {v sp i}");
+
+        let err = mismatch("t sp * i", "ab 1");
+        assert_eq!(err.expected, "a single char for `*`");
+        assert_eq!((err.column, err.len), (0, 2));
+
+        let err = mismatch("v nl {v c}\nnlnl\nv sp i", "ab\ncd\n");
+        assert_eq!(
+            err.message(),
+            "expected section delimiter \"\\n\\n\", found nothing"
+        );
+        assert_eq!((err.line, err.column), (2, 2));
+
+        let Err(ParseInputError::Input(err)) = crate::parse_input!("hm \", \" c f", "a 1.5, b2")
+        else {
+            panic!("expected input error");
+        };
+        assert_eq!(err.expected, "space between key and value");
+        assert_eq!(err.column, 7);
+
+        // Specs given at runtime can be malformed
+        let err = parse_input("v nl x", "1").unwrap_err();
+        assert!(matches!(err, ParseInputError::Spec(_)), "{err:?}");
+        assert!(err.to_string().contains("--> spec:1:6"));
+
+        // So can specs built by hand, they are spec errors like with the nom parser
+        let vec = "v sp i".parse::<Specification>().unwrap();
+        let invalid = [
+            (
+                Specification {
+                    delimiter: Delimiter::Space,
+                    container: ContainerType::Vec(InnerType::Wildcard(Wildcard::Chars)),
+                },
+                "wildcards are only allowed as tuple fields",
+            ),
+            (
+                Specification {
+                    delimiter: Delimiter::None,
+                    container: ContainerType::Range(RangeKind::Exclusive, IntType::I64),
+                },
+                "ranges need a delimiter between their numbers",
+            ),
+        ];
+        for (spec, message) in invalid {
+            let Err(ParseInputError::Spec(err)) = spec.interpret("1 2") else {
+                panic!("expected spec error for {spec:?}");
+            };
+            assert_eq!(err.message, message);
+        }
+        for (specifications, macro_delimiters, repeat_last, message) in [
+            (
+                vec![],
+                vec![],
+                false,
+                "specification list needs at least one specification",
+            ),
+            (
+                vec![vec.clone()],
+                vec![],
+                true,
+                "`...` repeats the last specification at a macro delimiter, there is none",
+            ),
+            (
+                vec![vec.clone(); 2],
+                vec![Delimiter::None],
+                false,
+                "macro delimiters need text between the sections",
+            ),
+            (
+                vec![vec.clone(); 2],
+                vec![],
+                false,
+                "every specification but the last needs a macro delimiter",
+            ),
+        ] {
+            let specs = SpecificationList {
+                specifications,
+                macro_delimiters,
+                repeat_last,
+            };
+            let Err(ParseInputError::Spec(err)) = specs.interpret("1 2") else {
+                panic!("expected spec error for {specs:?}");
+            };
+            assert_eq!(err.message, message);
+        }
+    }
+}
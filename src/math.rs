@@ -40,7 +40,7 @@ impl Polynom {
     pub fn new_cyclotomic(n: usize, cycl: &[Polynom]) -> Self {
         let mut poly = Self::x_n_1(n);
         for i in 1..=n / 2 {
-            if n % i == 0 {
+            if n.is_multiple_of(i) {
                 poly = poly.div(&cycl[i - 1]).unwrap();
             }
         }
@@ -223,8 +223,7 @@ impl Polynom {
             .coefficients
             .iter()
             .enumerate()
-            .filter(|(_, c)| !c.is_zero())
-            .last()
+            .rfind(|(_, c)| !c.is_zero())
         {
             self.coefficients.truncate(max_i + 1);
         } else {
@@ -414,7 +413,7 @@ pub fn gcd(mut a: u128, mut b: u128) -> u128 {
     (a, b) = if a >= b { (a, b) } else { (b, a) };
 
     while a > 1 || b > 1 {
-        if a % b == 0 {
+        if a.is_multiple_of(b) {
             return b;
         }
 
@@ -425,7 +424,7 @@ pub fn gcd(mut a: u128, mut b: u128) -> u128 {
 
 pub fn check_prime(n: u64, primes: &[u64]) -> bool {
     for &p in primes {
-        if n % p == 0 {
+        if n.is_multiple_of(p) {
             return false;
         }
         if p * p > n {
@@ -479,9 +478,9 @@ pub fn factorize_with_primes(mut num: u128, primes: &[u64]) -> Vec<(u128, usize)
         if p > num {
             break;
         }
-        if num % p == 0 {
+        if num.is_multiple_of(p) {
             let mut count = 0;
-            while num % p == 0 {
+            while num.is_multiple_of(p) {
                 count += 1;
                 num /= p;
             }
@@ -0,0 +1,753 @@
+use std::{borrow::Cow, cmp::Reverse, collections::HashMap, sync::Arc};
+
+use nom::{
+    character::complete::{anychar, digit1, one_of},
+    combinator::{all_consuming, opt, recognize},
+    error::{ErrorKind, ParseError},
+    number::complete::double,
+    sequence::pair,
+    Err, IResult,
+};
+
+use crate::{
+    grid::Grid,
+    interpreter::{length_end, parse_int, FromValue, Value},
+    ContainerType, Delimiter, InnerType, IntType, RangeKind, Specification, SpecificationList,
+    TemplatePart, Wildcard,
+};
+
+/// nom parser compiled from a spec, see `Compile`
+pub type SpecParser<'a, E> = Box<dyn Fn(&'a str) -> IResult<&'a str, Value<'a>, E> + 'a>;
+
+/// Parser of the text matched by a wildcard
+type Skip<'a, E> = Box<dyn Fn(&'a str) -> IResult<&'a str, &'a str, E> + 'a>;
+
+/// Compilation of a (part of a) specification into a nom parser, as an alternative to
+/// `Interpret` that can be combined with hand-written nom parsers:
+///
+/// ```ignore
+/// let draws = parse_specs("v \"; \" {v \", \" {t sp u32 {str}}}").unwrap().compile();
+/// let (rest, (id, draws)) = tuple((preceded(tag("Game "), u32), preceded(tag(": "), draws)))(line)?;
+/// ```
+///
+/// The parsers behave like nom's: they match a prefix of their input and leave the rest.
+/// Elements are separated like by `separated_list0`, so an element that doesn't match after
+/// a delimiter ends its container before that delimiter. Where the interpreter splits the
+/// input at the delimiters of the outer specs first, a compiled parser stops at them: an
+/// element of `v nl {v sp {str}}` ends at a space or a line break. Values are the same as
+/// those of `Interpret`.
+pub trait Compile {
+    /// Parser that stops at any of `ends`, which are the delimiters following the spec in
+    /// the surrounding grammar. Strings and wildcards run up to the end of the input otherwise.
+    fn compile_until<'a, E: ParseError<&'a str> + 'a>(&self, ends: &[&str]) -> SpecParser<'a, E>;
+
+    fn compile<'a, E: ParseError<&'a str> + 'a>(&self) -> SpecParser<'a, E> {
+        self.compile_until(&[])
+    }
+}
+
+impl Compile for InnerType {
+    fn compile_until<'a, E: ParseError<&'a str> + 'a>(&self, ends: &[&str]) -> SpecParser<'a, E> {
+        compile_inner(self, &to_owned(ends))
+    }
+}
+
+impl Compile for Specification {
+    fn compile_until<'a, E: ParseError<&'a str> + 'a>(&self, ends: &[&str]) -> SpecParser<'a, E> {
+        compile_spec(self, &to_owned(ends))
+    }
+}
+
+/// Sections are separated by the macro delimiters, multiple sections are parsed as a tuple.
+/// The sections of a repeated last specification are parsed as a list.
+impl Compile for SpecificationList {
+    fn compile_until<'a, E: ParseError<&'a str> + 'a>(&self, ends: &[&str]) -> SpecParser<'a, E> {
+        let ends = to_owned(ends);
+        // Sections can't be told apart without text between them, each specification but the
+        // last is followed by a macro delimiter, a repeated one by the last delimiter again
+        let Some(last) = self.specifications.last() else {
+            return invalid();
+        };
+        let repeated_delimiter = self.macro_delimiters.last().filter(|_| self.repeat_last);
+        if self.macro_delimiters.len() + 1 != self.specifications.len()
+            || self.macro_delimiters.iter().any(|d| d.text().is_none())
+            || (self.repeat_last && repeated_delimiter.is_none())
+        {
+            return invalid();
+        }
+        let mut sections = vec![];
+        for (spec, delimiter) in self.specifications.iter().zip(&self.macro_delimiters) {
+            sections.push((
+                compile_spec(spec, &with(&ends, delimiter)),
+                Some(delimiter.clone()),
+            ));
+        }
+        let repeated = repeated_delimiter.map(|delimiter| {
+            (
+                compile_spec(last, &with(&ends, delimiter)),
+                delimiter.clone(),
+            )
+        });
+        if repeated.is_none() {
+            sections.push((compile_spec(last, &ends), None));
+        }
+        Box::new(move |mut input| {
+            let mut values = vec![];
+            for (section, delimiter) in &sections {
+                let (rest, value) = section(input)?;
+                values.push(value);
+                input = match delimiter {
+                    Some(delimiter) => tag(delimiter, rest)?,
+                    None => rest,
+                };
+            }
+            if let Some((section, delimiter)) = &repeated {
+                let (rest, repeated) = elements(delimiter, &ends, input, section)?;
+                values.push(Value::Vec(repeated));
+                input = rest;
+            }
+            Ok((input, single_or_tuple(values)))
+        })
+    }
+}
+
+/// Parser of the typed value of the spec, like `parse_input!` the type has to match it.
+/// This is what `nom_parser!` expands to.
+pub fn typed<'a, T: FromValue<'a>, E: ParseError<&'a str> + 'a>(
+    parser: SpecParser<'a, E>,
+) -> impl Fn(&'a str) -> IResult<&'a str, T, E> {
+    move |input| parser(input).map(|(rest, value)| (rest, T::from_value(value)))
+}
+
+fn to_owned(ends: &[&str]) -> Vec<Delimiter> {
+    ends.iter()
+        .map(|end| Delimiter::Custom((*end).into()))
+        .collect()
+}
+
+fn with(ends: &[Delimiter], end: &Delimiter) -> Vec<Delimiter> {
+    let mut ends = ends.to_vec();
+    ends.push(end.clone());
+    ends
+}
+
+/// Whether the input is used up or continues with one of the ends
+fn at_end(input: &str, ends: &[Delimiter]) -> bool {
+    input.is_empty() || ends.iter().any(|end| end.match_at(input).is_some())
+}
+
+/// Length of the input up to the first of the ends
+fn until_end(input: &str, ends: &[Delimiter]) -> usize {
+    ends.iter()
+        .filter_map(|end| end.find(input))
+        .map(|found| found.start)
+        .min()
+        .unwrap_or(input.len())
+}
+
+fn error<'a, E: ParseError<&'a str>>(input: &'a str, kind: ErrorKind) -> Err<E> {
+    Err::Error(E::from_error_kind(input, kind))
+}
+
+/// Parser of a part of a spec that `parse_specs` rejects and that can only be built by hand,
+/// like a wildcard outside of a tuple or a macro delimiter without text. It fails without backtracking.
+fn invalid<'a, E: ParseError<&'a str> + 'a>() -> SpecParser<'a, E> {
+    Box::new(|input| Err(Err::Failure(E::from_error_kind(input, ErrorKind::Verify))))
+}
+
+/// The input after the delimiter at its start
+fn tag<'a, E: ParseError<&'a str>>(
+    delimiter: &Delimiter,
+    input: &'a str,
+) -> Result<&'a str, Err<E>> {
+    delimiter
+        .match_at(input)
+        .map(|len| &input[len..])
+        .ok_or_else(|| error(input, ErrorKind::Tag))
+}
+
+fn single_or_tuple(mut values: Vec<Value>) -> Value {
+    if values.len() == 1 {
+        values.pop().unwrap()
+    } else {
+        Value::Tuple(values)
+    }
+}
+
+/// Parses a single char with the parser, for the elements of specs without a delimiter
+fn each_char<'a, E: ParseError<&'a str> + 'a>(parser: SpecParser<'a, E>) -> SpecParser<'a, E> {
+    Box::new(move |input| {
+        let (rest, c) = recognize(anychar)(input)?;
+        let (_, value) = all_consuming(&parser)(c)?;
+        Ok((rest, value))
+    })
+}
+
+/// Elements separated by the delimiter up to one of the ends. Like in the interpreter, runs
+/// of a repeated delimiter before the first and after the last element are skipped.
+fn elements<'a, T, E: ParseError<&'a str>>(
+    delimiter: &Delimiter,
+    ends: &[Delimiter],
+    input: &'a str,
+    mut element: impl FnMut(&'a str) -> IResult<&'a str, T, E>,
+) -> IResult<&'a str, Vec<T>, E> {
+    let skip_run = |input: &'a str| match delimiter {
+        Delimiter::Repeated(_) => delimiter.match_at(input).map_or(input, |len| &input[len..]),
+        _ => input,
+    };
+    let mut values = vec![];
+    let mut rest = skip_run(input);
+    while !at_end(rest, ends) {
+        let next = match (values.is_empty(), delimiter.text()) {
+            (true, _) | (false, None) => rest,
+            (false, Some(_)) => match delimiter.match_at(rest) {
+                Some(len) => &rest[len..],
+                None => break,
+            },
+        };
+        match element(next) {
+            Ok((after, value)) => {
+                values.push(value);
+                rest = after;
+            }
+            Err(Err::Error(_)) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    if at_end(skip_run(rest), ends) {
+        rest = skip_run(rest);
+    }
+    Ok((rest, values))
+}
+
+fn integer<'a, E: ParseError<&'a str> + 'a>(ty: IntType) -> SpecParser<'a, E> {
+    Box::new(move |input| {
+        let (rest, digits) = recognize(pair(opt(one_of("+-")), digit1))(input)?;
+        let value = parse_int(ty, digits).map_err(|_| error(input, ErrorKind::TooLarge))?;
+        Ok((rest, value))
+    })
+}
+
+/// Wildcards match like in the interpreter, `***` includes the line break after it
+fn wildcard<'a, E: ParseError<&'a str> + 'a>(
+    wildcard: Wildcard,
+    ends: &[Delimiter],
+    is_last: bool,
+) -> Skip<'a, E> {
+    let ends = with(ends, &Delimiter::NewLine);
+    match wildcard {
+        Wildcard::Char => Box::new(|input| recognize(anychar)(input)),
+        Wildcard::Chars => Box::new(move |input| {
+            let len = until_end(input, &ends);
+            Ok((&input[len..], &input[..len]))
+        }),
+        Wildcard::Line => Box::new(move |input| {
+            let len = if is_last {
+                until_end(input, &ends)
+            } else {
+                input.find('\n').map_or(input.len(), |i| i + 1)
+            };
+            Ok((&input[len..], &input[..len]))
+        }),
+    }
+}
+
+fn compile_inner<'a, E: ParseError<&'a str> + 'a>(
+    inner: &InnerType,
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    match inner {
+        InnerType::Integer(ty) => integer(*ty),
+        InnerType::Float => Box::new(|input| {
+            let (rest, f) = double(input)?;
+            Ok((rest, Value::Float(f)))
+        }),
+        InnerType::Char => Box::new(|input| {
+            let (rest, c) = anychar(input)?;
+            Ok((rest, Value::Char(c)))
+        }),
+        // Longer keys are tried first, so that a key isn't cut off at another one. Enums are
+        // resolved from the token of their variant.
+        InnerType::Table(table) => {
+            let mut entries = table
+                .entries
+                .iter()
+                .map(|(key, value)| (key.text().into_owned(), *value))
+                .collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| Reverse(key.len()));
+            let is_enum = table.name.is_some();
+            Box::new(move |input| {
+                let (key, value) = entries
+                    .iter()
+                    .find(|(key, _)| input.starts_with(key.as_str()))
+                    .ok_or_else(|| error(input, ErrorKind::Alt))?;
+                let (token, rest) = input.split_at(key.len());
+                let value = if is_enum {
+                    Value::Str(Cow::Borrowed(token))
+                } else {
+                    Value::Int((*value).into())
+                };
+                Ok((rest, value))
+            })
+        }
+        InnerType::Nested(spec) => compile_spec(spec, ends),
+        // Wildcards have no value, they are only skipped as tuple fields
+        InnerType::Wildcard(_) => invalid(),
+    }
+}
+
+/// Parser of the elements of a container, each one char long without a delimiter
+fn compile_element<'a, E: ParseError<&'a str> + 'a>(
+    delimiter: &Delimiter,
+    inner: &InnerType,
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    match delimiter.text() {
+        Some(_) => compile_inner(inner, &with(ends, delimiter)),
+        None => each_char(compile_inner(inner, &[])),
+    }
+}
+
+fn compile_tuple<'a, E: ParseError<&'a str> + 'a>(
+    delimiter: &Delimiter,
+    inners: &[InnerType],
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    enum Field<'a, E> {
+        Value(SpecParser<'a, E>),
+        Skip(Skip<'a, E>),
+    }
+
+    let fields = inners
+        .iter()
+        .enumerate()
+        .map(|(i, inner)| {
+            let is_last = i + 1 == inners.len();
+            let field_ends = match delimiter.text() {
+                Some(_) if !is_last => with(ends, delimiter),
+                _ => ends.to_vec(),
+            };
+            // `***` is terminated by its line break instead of the delimiter
+            let delimited = !is_last && !matches!(inner, InnerType::Wildcard(Wildcard::Line));
+            let field = match inner {
+                InnerType::Wildcard(w) => Field::Skip(wildcard(*w, &field_ends, is_last)),
+                _ if delimiter.text().is_none() && !is_last => {
+                    Field::Value(each_char(compile_inner(inner, &[])))
+                }
+                _ => Field::Value(compile_inner(inner, &field_ends)),
+            };
+            (
+                field,
+                (delimited && delimiter.text().is_some()).then(|| delimiter.clone()),
+            )
+        })
+        .collect::<Vec<_>>();
+    Box::new(move |mut input| {
+        let mut values = vec![];
+        for (field, delimiter) in &fields {
+            input = match field {
+                Field::Value(parser) => {
+                    let (rest, value) = parser(input)?;
+                    values.push(value);
+                    rest
+                }
+                Field::Skip(parser) => parser(input)?.0,
+            };
+            if let Some(delimiter) = delimiter {
+                input = tag(delimiter, input)?;
+            }
+        }
+        Ok((input, single_or_tuple(values)))
+    })
+}
+
+/// Rows are lines, which have to consist of the same number of cells
+fn compile_grid<'a, E: ParseError<&'a str> + 'a>(
+    delimiter: &Delimiter,
+    inner: &InnerType,
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    let row_ends = with(ends, &Delimiter::NewLine);
+    let cell = compile_element(delimiter, inner, &row_ends);
+    let (delimiter, ends) = (delimiter.clone(), ends.to_vec());
+    Box::new(move |input| {
+        let mut width = None;
+        let (rest, rows) = elements(&Delimiter::NewLine, &ends, input, |row| {
+            let (rest, cells) = elements(&delimiter, &row_ends, row, &cell)?;
+            if *width.get_or_insert(cells.len()) != cells.len() {
+                return Err(error(row, ErrorKind::Verify));
+            }
+            Ok((rest, cells))
+        })?;
+        let grid = Grid::from_rows(rows).expect("rows are checked to have the same width");
+        Ok((rest, Value::Grid(grid)))
+    })
+}
+
+/// Each placeholder ends at the literal text following it
+fn compile_template<'a, E: ParseError<&'a str> + 'a>(
+    parts: &[TemplatePart],
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    enum Part<'a, E> {
+        Literal(Delimiter),
+        Placeholder(Arc<str>, SpecParser<'a, E>),
+    }
+
+    let parts = parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| match part {
+            TemplatePart::Literal(text) => Part::Literal(Delimiter::Custom(text.clone())),
+            TemplatePart::Placeholder(name, inner) => {
+                let field_ends = match parts.get(i + 1) {
+                    Some(TemplatePart::Literal(text)) => {
+                        with(ends, &Delimiter::Custom(text.clone()))
+                    }
+                    _ => ends.to_vec(),
+                };
+                Part::Placeholder(Arc::clone(name), compile_inner(inner, &field_ends))
+            }
+        })
+        .collect::<Vec<_>>();
+    Box::new(move |mut input| {
+        let mut fields = vec![];
+        for part in &parts {
+            input = match part {
+                Part::Literal(literal) => tag(literal, input)?,
+                Part::Placeholder(name, parser) => {
+                    let (rest, value) = parser(input)?;
+                    fields.push((Arc::clone(name), value));
+                    rest
+                }
+            };
+        }
+        Ok((input, Value::Record(fields)))
+    })
+}
+
+/// Key and value of an entry are separated by the separator. A duplicate key is a failure
+/// rather than the end of the map, unless the values are collected in a multimap.
+fn compile_map<'a, E: ParseError<&'a str> + 'a>(
+    spec: &Specification,
+    separator: &Delimiter,
+    key: &InnerType,
+    value: &InnerType,
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    let is_multimap = matches!(spec.container, ContainerType::MultiMap(..));
+    let entry_ends = match spec.delimiter.text() {
+        Some(_) => with(ends, &spec.delimiter),
+        None => ends.to_vec(),
+    };
+    let key = compile_inner(key, &with(&entry_ends, separator));
+    let value = compile_inner(value, &entry_ends);
+    let (delimiter, separator, ends) = (spec.delimiter.clone(), separator.clone(), ends.to_vec());
+    Box::new(move |input| {
+        let mut entries = HashMap::new();
+        let (rest, _) = elements(&delimiter, &ends, input, |entry| {
+            let (rest, k) = key(entry)?;
+            let rest = tag(&separator, rest)?;
+            let (rest, v) = value(rest)?;
+            if is_multimap {
+                let Value::Vec(values) = entries.entry(k).or_insert_with(|| Value::Vec(vec![]))
+                else {
+                    unreachable!("values of a multimap are lists");
+                };
+                values.push(v);
+            } else if entries.insert(k, v).is_some() {
+                return Err(Err::Failure(E::from_error_kind(entry, ErrorKind::Verify)));
+            }
+            Ok((rest, ()))
+        })?;
+        Ok((rest, Value::Map(entries)))
+    })
+}
+
+fn compile_range<'a, E: ParseError<&'a str> + 'a>(
+    delimiter: &Delimiter,
+    kind: RangeKind,
+    ty: IntType,
+) -> SpecParser<'a, E> {
+    // The numbers of a range can't be told apart without a delimiter
+    if delimiter.text().is_none() {
+        return invalid();
+    }
+    let delimiter = delimiter.clone();
+    let number = integer(ty);
+    Box::new(move |input| {
+        let (rest, start) = number(input)?;
+        let rest = tag(&delimiter, rest)?;
+        let (rest, second) = number(rest)?;
+        let value = match kind {
+            RangeKind::Exclusive => Value::Range(Box::new(start), Box::new(second)),
+            RangeKind::Inclusive => Value::RangeInclusive(Box::new(start), Box::new(second)),
+            RangeKind::Length => {
+                let end = length_end(ty, &start, &second)
+                    .ok_or_else(|| error(input, ErrorKind::TooLarge))?;
+                Value::Range(Box::new(start), Box::new(end))
+            }
+        };
+        Ok((rest, value))
+    })
+}
+
+/// Length of the next chunk of `len` elements, `None` if the input ends before
+fn chunk_len(delimiter: &Delimiter, len: usize, ends: &[Delimiter], input: &str) -> Option<usize> {
+    let element_ends = match delimiter.text() {
+        Some(_) => with(ends, delimiter),
+        None => ends.to_vec(),
+    };
+    let mut end = 0;
+    for i in 0..len {
+        if i > 0 && at_end(&input[end..], ends) {
+            return None;
+        }
+        match delimiter.text() {
+            Some(_) => {
+                if i > 0 {
+                    end += delimiter.match_at(&input[end..])?;
+                }
+                end += until_end(&input[end..], &element_ends);
+            }
+            None => end += input[end..].chars().next()?.len_utf8(),
+        }
+    }
+    Some(end)
+}
+
+/// Each chunk is the text from its first to its last element, which is parsed as a whole
+fn compile_chunks<'a, E: ParseError<&'a str> + 'a>(
+    delimiter: &Delimiter,
+    len: usize,
+    inner: &InnerType,
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    let parser = compile_inner(inner, &[]);
+    let (delimiter, ends) = (delimiter.clone(), ends.to_vec());
+    Box::new(move |input| {
+        let (rest, chunks) = elements(&delimiter, &ends, input, |chunk| {
+            let end = chunk_len(&delimiter, len, &ends, chunk)
+                .ok_or_else(|| error(chunk, ErrorKind::Count))?;
+            let (_, value) = all_consuming(&parser)(&chunk[..end])?;
+            Ok((&chunk[end..], value))
+        })?;
+        Ok((rest, Value::Vec(chunks)))
+    })
+}
+
+fn compile_spec<'a, E: ParseError<&'a str> + 'a>(
+    spec: &Specification,
+    ends: &[Delimiter],
+) -> SpecParser<'a, E> {
+    let delimiter = spec.delimiter.clone();
+    let ends_owned = ends.to_vec();
+    match &spec.container {
+        ContainerType::Vec(inner) | ContainerType::HashSet(inner) => {
+            let element = compile_element(&delimiter, inner, ends);
+            let is_set = matches!(spec.container, ContainerType::HashSet(_));
+            Box::new(move |input| {
+                let (rest, values) = elements(&delimiter, &ends_owned, input, &element)?;
+                Ok((
+                    rest,
+                    if is_set {
+                        Value::Set(values.into_iter().collect())
+                    } else {
+                        Value::Vec(values)
+                    },
+                ))
+            })
+        }
+        // A different number of elements is an error at the start of the container
+        ContainerType::Array(len, inner) => {
+            let (len, element) = (*len, compile_element(&delimiter, inner, ends));
+            Box::new(move |input| {
+                let (rest, values) = elements(&delimiter, &ends_owned, input, &element)?;
+                if values.len() != len {
+                    return Err(error(input, ErrorKind::Count));
+                }
+                Ok((rest, Value::Vec(values)))
+            })
+        }
+        ContainerType::HashMap(separator, key, value)
+        | ContainerType::MultiMap(separator, key, value) => {
+            compile_map(spec, separator, key, value, ends)
+        }
+        ContainerType::Tuple(inners) => compile_tuple(&delimiter, inners, ends),
+        ContainerType::Grid(inner) => compile_grid(&delimiter, inner, ends),
+        ContainerType::Template(parts) => compile_template(parts, ends),
+        ContainerType::Range(kind, ty) => compile_range(&delimiter, *kind, *ty),
+        ContainerType::Chunks(len, inner) => compile_chunks(&delimiter, *len, inner, ends),
+        // The title runs up to the delimiter, the content is parsed after it
+        ContainerType::Section(inner) => {
+            let content = compile_inner(inner, ends);
+            let title_ends = with(ends, &delimiter);
+            Box::new(move |input| {
+                let len = until_end(input, &title_ends);
+                let rest = tag(&delimiter, &input[len..])?;
+                let (rest, value) = content(rest)?;
+                let title = Cow::Borrowed(&input[..len]);
+                Ok((rest, Value::Section(title, Box::new(value))))
+            })
+        }
+        ContainerType::String => Box::new(move |input| {
+            let len = until_end(input, &ends_owned);
+            Ok((&input[len..], Value::Str(Cow::Borrowed(&input[..len]))))
+        }),
+        ContainerType::FixedString(chars) => {
+            let chars = *chars;
+            Box::new(move |input| {
+                let len = until_end(input, &ends_owned);
+                if input[..len].chars().count() != chars {
+                    return Err(error(input, ErrorKind::Count));
+                }
+                Ok((&input[len..], Value::Str(Cow::Borrowed(&input[..len]))))
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        generate::{Constraints, Generator},
+        interpreter::Interpret,
+    };
+    use nom::{bytes::complete::tag, character::complete::u32, error::Error, sequence::tuple};
+
+    fn parse<'a>(specs: &SpecificationList, input: &'a str) -> Value<'a> {
+        let parser = specs.compile::<Error<&str>>();
+        let (_, value) = all_consuming(parser)(input.trim_end_matches('\n')).unwrap();
+        value
+    }
+
+    #[test]
+    fn test_same_values_as_interpreter() {
+        for specs in [
+            "v nl {v sp i}",
+            "t \": \" {str} {v sp i}\nnlnl\nv nlnl {t nl *** {v nl {v sp u32}}}",
+            "v nl {t \": \" ** {t sp * f c}}",
+            "hm \", \" c {set , i8}",
+            "mm ~,~ ~->~ c i\nnlnl\nhm nl \" = \" {str} {tpl \"(\" {left} \", \" {right} \")\"}",
+            "g ['.', '#' = 5]\nnlnl\ng sp i",
+            "v nl {tpl {from} \" = (\" {left} \", \" {right} \")\"}",
+            "t \": \" ** {ch 2 sp {rl sp i8}}\nnlnl\nv nl {t sp i {ri - u8} {r .. i}}",
+            "v nlnl {v nl {str}}",
+            "v nl {ch 2 , {t , i c}}",
+            "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {v sp u32}}\n...",
+            "v sp i\nnl\nsec ~=~ {v , u8}\n...",
+            "v nl {t sp {str5} {v3 , u8}}",
+        ] {
+            let specs = crate::parse_specs(specs).unwrap();
+            let mut generator = Generator::new(3, Constraints::default());
+            for _ in 0..20 {
+                let input = generator.generate(&specs);
+                assert_eq!(parse(&specs, &input), specs.interpret(&input).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_within_nom_grammar() {
+        let draws =
+            crate::nom_parser!("v \"; \" {v \", \" {t sp u32 [\"red\", \"green\", \"blue\"]}}");
+        let mut game = tuple((tag("Game "), u32, tag(": "), draws));
+        let result: IResult<&str, _> = game("Game 4: 1 red, 2 blue; 3 green\nGame 5: 1 red");
+        let (rest, (_, id, _, draws)) = result.unwrap();
+        assert_eq!(rest, "\nGame 5: 1 red");
+        assert_eq!(id, 4);
+        assert_eq!(draws, vec![vec![(1, 0), (2, 2)], vec![(3, 1)]]);
+
+        let spec = "v sp {str}".parse::<Specification>().unwrap();
+        let words = spec.compile_until::<Error<&str>>(&[")"]);
+        let (rest, value) = words("a b) c").unwrap();
+        assert_eq!(rest, ") c");
+        assert_eq!(Vec::<&str>::from_value(value), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_flexible_delimiters() {
+        let card = crate::nom_parser!("t ~|~ {v sp+ u32} {v sp+ u32}");
+        let result: IResult<&str, _> = card(" 41  8 | 83 86  6\nCard 2");
+        assert_eq!(result, Ok(("\nCard 2", (vec![41, 8], vec![83, 86, 6]))));
+
+        let specs = crate::parse_specs("v ~;~ {v ~,~ {str}}").unwrap();
+        let value = parse(&specs, "a , b ;c");
+        assert_eq!(value, specs.interpret("a , b ;c").unwrap());
+    }
+
+    #[test]
+    fn test_backtracking_and_errors() {
+        let numbers = crate::nom_parser!("v sp u8");
+        let result: IResult<&str, Vec<u8>> = numbers("1 2 end");
+        assert_eq!(result, Ok((" end", vec![1, 2])));
+        let result: IResult<&str, Vec<u8>> = numbers("1 2 300");
+        assert_eq!(result, Ok((" 300", vec![1, 2])));
+
+        let spec = "t sp u8 u8".parse::<Specification>().unwrap();
+        let pair = spec.compile::<Error<&str>>();
+        assert_eq!(
+            pair("1 300").unwrap_err(),
+            Err::Error(Error::new("300", ErrorKind::TooLarge))
+        );
+        assert_eq!(
+            pair("1,2").unwrap_err(),
+            Err::Error(Error::new(",2", ErrorKind::Tag))
+        );
+
+        let spec = "hm , = c i".parse::<Specification>().unwrap();
+        let map = spec.compile::<Error<&str>>();
+        assert_eq!(
+            map("a=1,b=2,a=3").unwrap_err(),
+            Err::Failure(Error::new("a=3", ErrorKind::Verify))
+        );
+
+        let triple = crate::nom_parser!("v3 sp u8");
+        let result: IResult<&str, [u8; 3]> = triple("1 2 3\n4");
+        assert_eq!(result, Ok(("\n4", [1, 2, 3])));
+        let result: IResult<&str, [u8; 3]> = triple("1 2\n3");
+        assert_eq!(
+            result.unwrap_err(),
+            Err::Error(Error::new("1 2\n3", ErrorKind::Count))
+        );
+
+        let spec = "g c".parse::<Specification>().unwrap();
+        let grid = spec.compile::<Error<&str>>();
+        let (rest, value) = grid("ab\ncd\ne").unwrap();
+        assert_eq!(rest, "\ne");
+        assert_eq!(Grid::<char>::from_value(value).height(), 2);
+
+        // Specs built by hand that `parse_specs` rejects fail instead of panicking
+        let skip = InnerType::Wildcard(Wildcard::Chars).compile::<Error<&str>>();
+        assert_eq!(
+            skip("ab").unwrap_err(),
+            Err::Failure(Error::new("ab", ErrorKind::Verify))
+        );
+        let spec = Specification {
+            delimiter: Delimiter::None,
+            container: ContainerType::Range(RangeKind::Exclusive, IntType::U8),
+        };
+        assert!(matches!(
+            spec.compile::<Error<&str>>()("12").unwrap_err(),
+            Err::Failure(_)
+        ));
+        let vec = "v sp i".parse::<Specification>().unwrap();
+        for (specifications, macro_delimiters, repeat_last) in [
+            (vec![], vec![], false),
+            (vec![vec.clone()], vec![], true),
+            (vec![vec.clone(); 2], vec![Delimiter::None], false),
+            (vec![vec.clone(); 2], vec![], false),
+        ] {
+            let specs = SpecificationList {
+                specifications,
+                macro_delimiters,
+                repeat_last,
+            };
+            assert!(matches!(
+                specs.compile::<Error<&str>>()("1 2").unwrap_err(),
+                Err::Failure(_)
+            ));
+        }
+    }
+}
@@ -0,0 +1,434 @@
+use std::fmt::Display;
+
+use crate::{
+    interpreter::Value, ContainerType, Delimiter, InnerType, RangeKind, Specification,
+    SpecificationList, TemplatePart, Wildcard,
+};
+
+// Wildcards have no value, they are rendered as the shortest text they match. Values that
+// contain their own delimiters can't be read back the same way, e.g. a `str` with a space in
+// a `v sp {str}`.
+
+/// Value that doesn't match the spec it is rendered with, located by its path within the
+/// whole value like `[1].0`. The innermost spec around the mismatch is filled in while the
+/// error propagates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderError {
+    /// The spec node that the value should match, in spec syntax
+    pub spec: String,
+    /// The value found instead, debug formatted
+    pub found: String,
+    /// Indices, fields and keys leading to the value, empty for the whole value
+    pub path: String,
+}
+
+impl RenderError {
+    fn new(spec: &impl Display, value: &Value) -> Self {
+        RenderError {
+            spec: spec.to_string(),
+            found: format!("{value:?}"),
+            path: String::new(),
+        }
+    }
+
+    /// The error of a part of a value, `segment` leads from the value to the part
+    fn at(mut self, segment: impl Display) -> Self {
+        self.path = format!("{segment}{}", self.path);
+        self
+    }
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "error: can't render {} as `{}`\n  --> value{}",
+            self.found, self.spec, self.path
+        )
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+fn render_wildcard(wildcard: Wildcard, out: &mut String) {
+    match wildcard {
+        Wildcard::Char => out.push('_'),
+        Wildcard::Chars | Wildcard::Line => {}
+    }
+}
+
+fn render_inner(inner: &InnerType, value: &Value, out: &mut String) -> Result<(), RenderError> {
+    match (inner, value) {
+        (InnerType::Integer(_), Value::Int(i)) => out.push_str(&i.to_string()),
+        (InnerType::Integer(_), Value::UInt(u)) => out.push_str(&u.to_string()),
+        // Display prints the shortest text that parses back to the same float
+        (InnerType::Float, Value::Float(f)) => out.push_str(&f.to_string()),
+        (InnerType::Char, Value::Char(c)) => out.push(*c),
+        (InnerType::Table(table), Value::Str(token)) if table.name.is_some() => out.push_str(token),
+        (InnerType::Table(table), Value::Int(i)) => {
+            match table.entries.iter().find(|(_, v)| i128::from(*v) == *i) {
+                Some((key, _)) => out.push_str(&key.text()),
+                None => return Err(RenderError::new(inner, value)),
+            }
+        }
+        (InnerType::Nested(spec), value) => render_spec(spec, value, out)?,
+        (inner, value) => return Err(RenderError::new(inner, value)),
+    }
+    Ok(())
+}
+
+fn render_number(spec: &Specification, value: &Value) -> Result<String, RenderError> {
+    match value {
+        Value::Int(i) => Ok(i.to_string()),
+        Value::UInt(u) => Ok(u.to_string()),
+        _ => Err(RenderError::new(spec, value)),
+    }
+}
+
+/// Renders the values separated by the delimiter, errors are located by the index
+fn render_joined<'a, 'b: 'a>(
+    delimiter: &Delimiter,
+    values: impl IntoIterator<Item = &'a Value<'b>>,
+    mut render: impl FnMut(&Value, &mut String) -> Result<(), RenderError>,
+    out: &mut String,
+) -> Result<(), RenderError> {
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            out.push_str(delimiter.text().unwrap_or_default());
+        }
+        render(value, out).map_err(|e| e.at(format_args!("[{i}]")))?;
+    }
+    Ok(())
+}
+
+/// Renders the entries of a map, errors are located by the key
+fn render_entries<'a, 'b: 'a>(
+    spec: &Specification,
+    separator: &Delimiter,
+    (key, value): (&InnerType, &InnerType),
+    entries: impl IntoIterator<Item = (&'a Value<'b>, &'a Value<'b>)>,
+    out: &mut String,
+) -> Result<(), RenderError> {
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            out.push_str(spec.delimiter.text().unwrap_or_default());
+        }
+        render_inner(key, k, out).map_err(|e| e.at(format_args!("[{k:?}]")))?;
+        out.push_str(separator.text().unwrap_or_default());
+        render_inner(value, v, out).map_err(|e| e.at(format_args!("[{k:?}]")))?;
+    }
+    Ok(())
+}
+
+fn render_spec(spec: &Specification, value: &Value, out: &mut String) -> Result<(), RenderError> {
+    let mismatch = || RenderError::new(spec, value);
+    match (&spec.container, value) {
+        (ContainerType::Vec(inner) | ContainerType::Array(_, inner), Value::Vec(values)) => {
+            render_joined(
+                &spec.delimiter,
+                values,
+                |v, out| render_inner(inner, v, out),
+                out,
+            )
+        }
+        (ContainerType::HashSet(inner), Value::Set(values)) => render_joined(
+            &spec.delimiter,
+            values,
+            |v, out| render_inner(inner, v, out),
+            out,
+        ),
+        (ContainerType::HashMap(separator, key, value), Value::Map(entries)) => {
+            render_entries(spec, separator, (key, value), entries, out)
+        }
+        // Every value of a multimap is an entry of its own
+        (ContainerType::MultiMap(separator, key, value), Value::Map(entries)) => {
+            let mut pairs = vec![];
+            for (k, values) in entries {
+                let values = values
+                    .as_slice()
+                    .ok_or_else(|| RenderError::new(spec, values).at(format_args!("[{k:?}]")))?;
+                pairs.extend(values.iter().map(|v| (k, v)));
+            }
+            render_entries(spec, separator, (key, value), pairs, out)
+        }
+        (ContainerType::Tuple(inners), value) => {
+            let fields = inners
+                .iter()
+                .filter(|inner| !matches!(inner, InnerType::Wildcard(_)))
+                .count();
+            // A single field is not wrapped in a tuple
+            let values = match value {
+                Value::Tuple(values) if fields > 1 && values.len() == fields => values.as_slice(),
+                _ if fields == 1 => std::slice::from_ref(value),
+                _ => return Err(mismatch()),
+            };
+            let mut values = values.iter().enumerate();
+            for (i, inner) in inners.iter().enumerate() {
+                match inner {
+                    InnerType::Wildcard(wildcard) => render_wildcard(*wildcard, out),
+                    _ => {
+                        let (j, value) = values.next().ok_or_else(mismatch)?;
+                        render_inner(inner, value, out).map_err(|e| match fields {
+                            1 => e,
+                            _ => e.at(format_args!(".{j}")),
+                        })?;
+                    }
+                }
+                if i + 1 < inners.len() {
+                    // `***` is terminated by its newline instead of the delimiter
+                    if let InnerType::Wildcard(Wildcard::Line) = inner {
+                        out.push('\n');
+                    } else {
+                        out.push_str(spec.delimiter.text().unwrap_or_default());
+                    }
+                }
+            }
+            Ok(())
+        }
+        (ContainerType::Grid(inner), Value::Grid(grid)) => {
+            for (i, row) in grid.rows().enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                }
+                render_joined(
+                    &spec.delimiter,
+                    row,
+                    |v, out| render_inner(inner, v, out),
+                    out,
+                )
+                .map_err(|e| e.at(format_args!("[{i}]")))?;
+            }
+            Ok(())
+        }
+        // Typed templates are tuples of their fields
+        (ContainerType::Template(parts), Value::Record(_) | Value::Tuple(_)) => {
+            let fields: Vec<(String, &Value)> = match value {
+                Value::Record(fields) => fields
+                    .iter()
+                    .map(|(name, v)| (format!(".{name}"), v))
+                    .collect(),
+                Value::Tuple(values) => values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| (format!(".{i}"), v))
+                    .collect(),
+                _ => return Err(mismatch()),
+            };
+            let mut fields = fields.into_iter();
+            for part in parts {
+                match part {
+                    TemplatePart::Literal(text) => out.push_str(text),
+                    TemplatePart::Placeholder(_, inner) => {
+                        let (path, value) = fields.next().ok_or_else(mismatch)?;
+                        render_inner(inner, value, out).map_err(|e| e.at(path))?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        (
+            ContainerType::Range(kind, _),
+            Value::Range(start, end) | Value::RangeInclusive(start, end),
+        ) => {
+            let second = match (kind, &**start, &**end) {
+                (RangeKind::Length, Value::Int(start), Value::Int(end)) => {
+                    (end - start).to_string()
+                }
+                (RangeKind::Length, Value::UInt(start), Value::UInt(end)) => {
+                    (end - start).to_string()
+                }
+                (RangeKind::Length, ..) => return Err(mismatch()),
+                (_, _, end) => render_number(spec, end)?,
+            };
+            out.push_str(&render_number(spec, start)?);
+            out.push_str(spec.delimiter.text().unwrap_or_default());
+            out.push_str(&second);
+            Ok(())
+        }
+        (ContainerType::Chunks(_, inner), Value::Vec(values)) => render_joined(
+            &spec.delimiter,
+            values,
+            |v, out| render_inner(inner, v, out),
+            out,
+        ),
+        // Typed sections are tuples of title and content
+        (ContainerType::Section(inner), Value::Section(title, content)) => {
+            out.push_str(title);
+            out.push_str(spec.delimiter.text().unwrap_or_default());
+            render_inner(inner, content, out).map_err(|e| e.at(".content"))
+        }
+        (ContainerType::Section(inner), Value::Tuple(values)) if values.len() == 2 => {
+            let title = values[0].as_str().ok_or_else(mismatch)?;
+            out.push_str(title);
+            out.push_str(spec.delimiter.text().unwrap_or_default());
+            render_inner(inner, &values[1], out).map_err(|e| e.at(".1"))
+        }
+        (ContainerType::String | ContainerType::FixedString(_), Value::Str(s)) => {
+            out.push_str(s);
+            Ok(())
+        }
+        // Typed fixed-length strings are arrays of their chars
+        (ContainerType::FixedString(_), Value::Vec(chars)) => render_joined(
+            &Delimiter::None,
+            chars,
+            |c, out| render_inner(&InnerType::Char, c, out),
+            out,
+        ),
+        _ => Err(mismatch()),
+    }
+}
+
+/// Inverse of `Interpret`: writes a value as text following the spec, so that interpreting
+/// the text gives the value back. A value that doesn't match the spec is an error.
+pub trait Render {
+    fn render(&self, value: &Value) -> Result<String, RenderError>;
+}
+
+impl Render for InnerType {
+    fn render(&self, value: &Value) -> Result<String, RenderError> {
+        let mut out = String::new();
+        render_inner(self, value, &mut out)?;
+        Ok(out)
+    }
+}
+
+impl Render for Specification {
+    fn render(&self, value: &Value) -> Result<String, RenderError> {
+        let mut out = String::new();
+        render_spec(self, value, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// Joins the sections with the macro delimiters, multiple sections are given as a tuple and
+/// the sections of a repeated last specification as a list
+impl Render for SpecificationList {
+    fn render(&self, value: &Value) -> Result<String, RenderError> {
+        let mismatch = || RenderError::new(self, value);
+        let sections = match value {
+            Value::Tuple(sections) if self.specifications.len() > 1 => sections.as_slice(),
+            _ if self.specifications.len() == 1 => std::slice::from_ref(value),
+            _ => return Err(mismatch()),
+        };
+        if sections.len() != self.specifications.len()
+            || self.macro_delimiters.len() + 1 != self.specifications.len()
+        {
+            return Err(mismatch());
+        }
+        let mut out = String::new();
+        for (i, (spec, section)) in self.specifications.iter().zip(sections).enumerate() {
+            if i > 0 {
+                out.push_str(self.macro_delimiters[i - 1].text().unwrap_or_default());
+            }
+            let at_section = |e: RenderError| match sections.len() {
+                1 => e,
+                _ => e.at(format_args!(".{i}")),
+            };
+            match self.macro_delimiters.last() {
+                Some(delimiter) if self.repeat_last && i + 1 == self.specifications.len() => {
+                    let repeated = section
+                        .as_slice()
+                        .ok_or_else(|| at_section(RenderError::new(spec, section)))?;
+                    render_joined(
+                        delimiter,
+                        repeated,
+                        |v, out| render_spec(spec, v, out),
+                        &mut out,
+                    )
+                    .map_err(at_section)?;
+                }
+                _ => render_spec(spec, section, &mut out).map_err(at_section)?,
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::{parse_input, Interpret, IntoValue};
+
+    #[test]
+    fn test_round_trip() {
+        for (specs, input) in [
+            ("v nl {v sp i}", "1 2 3\n-4 5 6"),
+            (
+                "t \": \" {str} {v sp i}\nnlnl\nv nlnl {t nl *** {v nl {v sp i}}}",
+                "seeds: 79 14\n\na-to-b map:\n1 2 3\n\nb-to-c map:\n4 5 6\n7 8 9",
+            ),
+            (
+                "v nl {t \": \" ** {t sp * f c}}",
+                "Card 1: # 0.5 x\nCard 2: - -2 y",
+            ),
+            ("hm \", \" c {set , i}", "a 1,2, b 3"),
+            ("mm ~,~ ~->~ c i", "a -> 1, b -> 2, a -> 3"),
+            ("g ['.', '#' = 5]\nnlnl\ng sp i", "#..\n.#.\n\n1 2\n3 4"),
+            ("v \", \" [\"red\", \"green\", 'b']", "green, b, red"),
+            ("v sp [Color: \"red\", 'b']", "b red"),
+            (
+                "t sp i {rl sp i}\nnl\nch 2 sp {t sp u8 {ri - u8}}",
+                "50 98 2\n3 2-4 8 5-5",
+            ),
+            (
+                "v nl {tpl {from} \" = (\" {left} \", \" {right} \")\"}",
+                "AAA = (BBB, CCC)\nBBB = (DDD, EEE)",
+            ),
+            (
+                "sec \": \" {v sp i}\nnlnl\nsec \" map:\\n\" {v nl {v sp i}}\n...",
+                "seeds: 79 14\n\na-to-b map:\n1 2 3\n\nb-to-c map:\n4 5 6\n7 8 9",
+            ),
+        ] {
+            let specs = crate::parse_specs(specs).unwrap();
+            let value = specs.interpret(input).unwrap();
+            let rendered = specs.render(&value).unwrap();
+            assert_eq!(specs.interpret(&rendered).unwrap(), value);
+        }
+
+        let specs = crate::parse_specs("t \": \" ** {t sp * f c}").unwrap();
+        let value = parse_input("t \": \" ** {t sp * f c}", "Card 1: # 0.5 x").unwrap();
+        assert_eq!(specs.render(&value).unwrap(), ": _ 0.5 x");
+    }
+
+    #[test]
+    fn test_render_typed() {
+        let specs = crate::parse_specs("v nl {t sp {str} i}").unwrap();
+        let hands = vec![("32T3K".to_string(), 765), ("T55J5".to_string(), 684)];
+        assert_eq!(
+            specs.render(&hands.into_value()).unwrap(),
+            "32T3K 765\nT55J5 684"
+        );
+        let specs = crate::parse_specs("v nl {t sp {str5} {v3 , u8}}").unwrap();
+        let rows = vec![(['3', '2', 'T', '3', 'K'], [1, 2, 3])];
+        assert_eq!(specs.render(&rows.into_value()).unwrap(), "32T3K 1,2,3");
+    }
+
+    #[test]
+    fn test_render_mismatch() {
+        let specs = crate::parse_specs("v sp c").unwrap();
+        let err = specs
+            .render(&Value::Vec(vec![Value::Char('a'), Value::Int(1)]))
+            .unwrap_err();
+        assert_eq!(
+            (err.spec.as_str(), err.found.as_str(), err.path.as_str()),
+            ("c", "Int(1)", "[1]")
+        );
+        assert_eq!(
+            err.to_string(),
+            "error: can't render Int(1) as `c`\n  --> value[1]"
+        );
+
+        // The path leads through sections, tuple fields and record fields
+        let specs =
+            crate::parse_specs("t sp i i\nnlnl\nv nl {tpl {from} \" = \" {to: t sp i c}}").unwrap();
+        let value = parse_input(
+            "t sp i i\nnlnl\nv nl {tpl {from} \" = \" {to: t sp i i}}",
+            "1 2\n\na = 3 4",
+        )
+        .unwrap();
+        let err = specs.render(&value).unwrap_err();
+        assert_eq!((err.spec.as_str(), err.path.as_str()), ("c", ".1[0].to.1"));
+        let err = specs.render(&Value::Int(1)).unwrap_err();
+        assert_eq!((err.found.as_str(), err.path.as_str()), ("Int(1)", ""));
+    }
+}
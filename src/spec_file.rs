@@ -0,0 +1,109 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{SpecError, SpecificationList};
+
+/// Failure while loading a spec file, either reading it or a spec line that doesn't parse
+#[derive(Debug)]
+pub enum SpecFileError {
+    Io(PathBuf, io::Error),
+    Spec(PathBuf, SpecError),
+}
+
+impl Display for SpecFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecFileError::Io(path, e) => write!(f, "error: {}: {e}", path.display()),
+            // The error is located within the file instead of the spec string
+            SpecFileError::Spec(path, e) => {
+                let location = format!("--> {}:", path.display());
+                let snippet = e.snippet().replacen("--> spec:", &location, 1);
+                write!(f, "error: {}\n{snippet}", e.message)
+            }
+        }
+    }
+}
+
+impl Error for SpecFileError {}
+
+/// Spec file shipped next to the inputs of a day: the day number the input file name starts
+/// with and the extension `spec`, so `in/5r.txt` and `in/5t.txt` share `in/5.spec`. A file
+/// name without a day number keeps its whole stem.
+pub fn spec_path(input: &Path) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let day_len = stem
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(stem.len());
+    let day = if day_len > 0 { &stem[..day_len] } else { &stem };
+    input.with_file_name(format!("{day}.spec"))
+}
+
+/// Reads a spec file, which contains a specification list as written in `parse_input!`
+/// without the string escaping of Rust
+pub fn read_spec(path: &Path) -> Result<SpecificationList, SpecFileError> {
+    let specs = fs::read_to_string(path).map_err(|e| SpecFileError::Io(path.into(), e))?;
+    specs
+        .parse()
+        .map_err(|e| SpecFileError::Spec(path.into(), e))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::Interpret;
+
+    #[test]
+    fn test_spec_path() {
+        for (input, spec) in [
+            ("in/5r.txt", "in/5.spec"),
+            ("in/10t3.txt", "in/10.spec"),
+            ("in/12r.txt", "in/12.spec"),
+            ("sample.txt", "sample.spec"),
+        ] {
+            assert_eq!(spec_path(Path::new(input)), Path::new(spec));
+        }
+    }
+
+    #[test]
+    fn test_shipped_specs() {
+        for (day, inputs) in [
+            (2, &["2t", "2r"][..]),
+            (4, &["4t", "4r"]),
+            (5, &["5t", "5r"]),
+            (7, &["7t", "7r"]),
+            (8, &["8t1", "8t2", "8t3", "8r"]),
+            (9, &["9t", "9r"]),
+            (11, &["11t", "11r"]),
+        ] {
+            let specs = read_spec(Path::new(&format!("in/{day}.spec"))).unwrap();
+            for input in inputs {
+                let path = format!("in/{input}.txt");
+                assert_eq!(
+                    spec_path(Path::new(&path)),
+                    Path::new(&format!("in/{day}.spec"))
+                );
+                let input = fs::read_to_string(path).unwrap();
+                specs.interpret(&input).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_spec_errors() {
+        let err = read_spec(Path::new("in/missing.spec")).unwrap_err();
+        assert!(matches!(err, SpecFileError::Io(..)));
+
+        let err = SpecFileError::Spec(
+            "in/0.spec".into(),
+            crate::parse_specs("v nl x").unwrap_err(),
+        );
+        assert!(err.to_string().contains("--> in/0.spec:1:6"));
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{self, BufRead},
+};
+
+use crate::{
+    interpreter::{Interpret, ParseInputError, Value},
+    ContainerType, InnerType, InputError, SpecError, Specification,
+};
+
+/// Failure while streaming records, either reading, a spec built by hand that is invalid or
+/// a record that doesn't match its spec
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Spec(SpecError),
+    Input(InputError),
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "error: {e}"),
+            StreamError::Spec(e) => e.fmt(f),
+            StreamError::Input(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+impl From<InputError> for StreamError {
+    fn from(e: InputError) -> Self {
+        StreamError::Input(e)
+    }
+}
+
+/// Elements of a `v` spec read lazily from a `BufRead`, so that only one record is in memory
+/// at a time:
+///
+/// ```ignore
+/// let spec = "v nl {t sp {str} {v , usize}}".parse::<Specification>().unwrap();
+/// let reader = BufReader::new(File::open("in/12r.txt")?);
+/// let count = Records::new(&spec, reader)?.par_bridge().map(...).sum();
+/// ```
+///
+/// Strings of the records are owned, as each record is read into a new buffer. Errors are
+/// located by their line within the whole input. Newlines at the end of the input are ignored
+/// like by `Interpret`, so both give the same records.
+pub struct Records<R> {
+    reader: R,
+    inner: InnerType,
+    delimiter: Vec<u8>,
+    /// Lines before the next record
+    lines: usize,
+    /// Newlines that are only part of the input if more than newlines follows
+    newlines: usize,
+    /// The byte after the held back newlines, once it has been read
+    after_newlines: Option<u8>,
+    /// Whether a delimiter has been read, after which there is at least one more record
+    delimited: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Records<R> {
+    /// Fails if the spec isn't a `v` with a delimiter that is neither padded nor repeated
+    pub fn new(spec: &Specification, reader: R) -> Result<Self, SpecError> {
+        let (ContainerType::Vec(inner), Some(delimiter), false) = (
+            &spec.container,
+            spec.delimiter.text(),
+            spec.delimiter.is_flexible(),
+        ) else {
+            let spec = spec.to_string();
+            return Err(SpecError {
+                message: "only `v` with a fixed delimiter can be streamed".into(),
+                line: 1,
+                column: 0,
+                len: spec.chars().count(),
+                source_line: spec,
+            });
+        };
+        Ok(Records {
+            reader,
+            inner: inner.clone(),
+            delimiter: delimiter.as_bytes().to_vec(),
+            lines: 0,
+            newlines: 0,
+            after_newlines: None,
+            delimited: false,
+            done: false,
+        })
+    }
+
+    /// Next byte of the input without the newlines at its end, `None` at the end
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        if self.after_newlines.is_some() {
+            if self.newlines > 0 {
+                self.newlines -= 1;
+                return Ok(Some(b'\n'));
+            }
+            return Ok(self.after_newlines.take());
+        }
+        loop {
+            let Some(&byte) = self.reader.fill_buf()?.first() else {
+                return Ok(None);
+            };
+            self.reader.consume(1);
+            if byte != b'\n' {
+                self.after_newlines = Some(byte);
+                return self.next_byte();
+            }
+            self.newlines += 1;
+        }
+    }
+
+    /// Next record without its delimiter, `None` at the end of the input
+    fn read_record(&mut self) -> io::Result<Option<String>> {
+        let mut record = vec![];
+        loop {
+            let Some(byte) = self.next_byte()? else {
+                self.done = true;
+                // Like `split`, input ending with a delimiter ends with an empty record
+                if record.is_empty() && !self.delimited {
+                    return Ok(None);
+                }
+                break;
+            };
+            record.push(byte);
+            if record.ends_with(&self.delimiter) {
+                record.truncate(record.len() - self.delimiter.len());
+                self.delimited = true;
+                break;
+            }
+        }
+        String::from_utf8(record)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<Value<'static>, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = match self.read_record() {
+            Ok(Some(record)) => record,
+            Ok(None) => return None,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e.into()));
+            }
+        };
+        let lines = self.lines;
+        self.lines +=
+            record.matches('\n').count() + self.delimiter.iter().filter(|b| **b == b'\n').count();
+        Some(
+            self.inner
+                .interpret(&record)
+                .map(Value::into_owned)
+                .map_err(|e| match e {
+                    ParseInputError::Spec(e) => StreamError::Spec(e),
+                    ParseInputError::Input(mut e) => {
+                        e.line += lines;
+                        StreamError::Input(e)
+                    }
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::FromValue;
+
+    #[test]
+    fn test_records() {
+        let spec = "v nl {v sp i}".parse::<Specification>().unwrap();
+        let records = Records::new(&spec, "1 2\n3 4\n".as_bytes())
+            .unwrap()
+            .map(|r| Vec::<i64>::from_value(r.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(records, vec![vec![1, 2], vec![3, 4]]);
+
+        let spec = "v nlnl {v nl {str}}".parse::<Specification>().unwrap();
+        let records = Records::new(&spec, "a\nb\n\nc\n".as_bytes())
+            .unwrap()
+            .map(|r| Vec::<String>::from_value(r.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(records, vec![vec!["a", "b"], vec!["c"]]);
+
+        let spec = "v \", \" i".parse::<Specification>().unwrap();
+        let records = Records::new(&spec, "1, 2, 3".as_bytes())
+            .unwrap()
+            .map(|r| r.unwrap().as_int().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let spec = "v nlnl {v nl i}".parse::<Specification>().unwrap();
+        let mut records = Records::new(&spec, "1\n2\n\n3\nx\n".as_bytes()).unwrap();
+        assert!(records.next().unwrap().is_ok());
+        let Some(Err(StreamError::Input(e))) = records.next() else {
+            panic!("Expected input error");
+        };
+        assert_eq!((e.line, e.column), (5, 0));
+        assert_eq!(e.message(), "expected integer, found \"x\"");
+        assert!(records.next().is_none());
+
+        let mut records = Records::new(&spec, [0xff, b'\n'].as_slice()).unwrap();
+        assert!(matches!(records.next(), Some(Err(StreamError::Io(_)))));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn test_trailing_newlines() {
+        // Records are the same as the elements `Interpret` gives for the whole input
+        for (spec, input) in [
+            ("v nl {v sp i}", "1 2\n3 4\n\n\n"),
+            ("v nl {v sp i}", "1 2\n\n3 4\n"),
+            ("v nl {v sp i}", "\n\n"),
+            ("v nlnl {v nl i}", "1\n2\n\n3\n\n\n"),
+            ("v \", \" {str}", "a, b, \n"),
+            ("v \",\\n\" {str}", "a,\nb,\n"),
+        ] {
+            let spec = spec.parse::<Specification>().unwrap();
+            let records = Records::new(&spec, input.as_bytes())
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let Value::Vec(elements) = spec.interpret(input.trim_end_matches('\n')).unwrap() else {
+                unreachable!()
+            };
+            assert_eq!(records, elements, "{spec} of {input:?}");
+        }
+
+        let input = "1 2\n3\n\n";
+        let streamed = crate::parse_stream!("v nl {v sp i}", input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            streamed,
+            crate::parse_input!("v nl {v sp i}", input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unstreamable_spec() {
+        for spec in ["t sp i i", "v sp+ i", "v i"] {
+            let spec = spec.parse::<Specification>().unwrap();
+            let err = Records::new(&spec, "".as_bytes()).err().unwrap();
+            assert_eq!(
+                err.message,
+                "only `v` with a fixed delimiter can be streamed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_stream_macro() {
+        let lines = crate::parse_stream!(
+            "v nl {t sp {str} {v , usize}}",
+            "???.### 1,1,3\n".as_bytes()
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        assert_eq!(lines, vec![("???.###".to_string(), vec![1, 1, 3])]);
+    }
+}
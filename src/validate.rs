@@ -0,0 +1,143 @@
+use crate::{
+    interpreter::{Interpret, ParseInputError},
+    InputError, SpecError, SpecificationList,
+};
+
+/// Whitespace that a spec would report at a confusing place or not at all: carriage returns,
+/// spaces and tabs at the end of a line and blank lines at the end of the input
+fn whitespace_problems(specs: &SpecificationList, input: &str) -> Vec<InputError> {
+    let spec = specs.to_string();
+    let mut problems = vec![];
+    let mut start = 0;
+    for line in input.split('\n') {
+        let content = line.trim_end_matches('\r');
+        let trimmed = content.trim_end_matches([' ', '\t']);
+        if trimmed.len() < content.len() {
+            problems.push(InputError::new(
+                "end of line",
+                &spec,
+                input,
+                start + trimmed.len(),
+                start + content.len(),
+            ));
+        }
+        if content.len() < line.len() {
+            problems.push(InputError::new(
+                "line break without carriage return",
+                &spec,
+                input,
+                start + content.len(),
+                start + line.len(),
+            ));
+        }
+        start += line.len() + 1;
+    }
+    // A single line break ends the last line, the found text of the others would be cut
+    // off at the first of them
+    let end = input.trim_end_matches('\n').len() + 1;
+    if end < input.len() {
+        problems.push(InputError {
+            found: format!("{:?}", &input[end..]),
+            ..InputError::new("end of input", &spec, input, end, input.len())
+        });
+    }
+    problems
+}
+
+/// Problems of an input file: CRLF line breaks, trailing whitespace and trailing blank lines,
+/// and where the input doesn't match its spec, like a ragged grid, an unexpected char or a
+/// missing section. Specs built by hand that are invalid are an error.
+pub fn validate(specs: &SpecificationList, input: &str) -> Result<Vec<InputError>, SpecError> {
+    let mut problems = whitespace_problems(specs, input);
+    match specs.interpret(input) {
+        Ok(_) => {}
+        Err(ParseInputError::Spec(e)) => return Err(e),
+        Err(ParseInputError::Input(e)) => problems.push(e),
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn messages(specs: &str, input: &str) -> Vec<(usize, usize, String)> {
+        let specs = crate::parse_specs(specs).unwrap();
+        validate(&specs, input)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.line, e.column, e.message()))
+            .collect()
+    }
+
+    #[test]
+    fn test_whitespace() {
+        assert_eq!(messages("v nl {v sp i}", "1 2\n3 4\n"), vec![]);
+        // Every line is reported, and the spec is checked as well
+        assert_eq!(
+            messages("v nl {v sp i}", "1 2\r\n3 4 \r\n"),
+            vec![
+                (
+                    1,
+                    3,
+                    "expected line break without carriage return, found \"\\r\"".into()
+                ),
+                (2, 3, "expected end of line, found \" \"".into()),
+                (
+                    2,
+                    4,
+                    "expected line break without carriage return, found \"\\r\"".into()
+                ),
+                (1, 2, "expected integer, found \"2\\r\"".into()),
+            ]
+        );
+        assert_eq!(
+            messages("v nl {v sp+ i}", "1 2 \n3\t4\t\t"),
+            vec![
+                (1, 3, "expected end of line, found \" \"".into()),
+                (2, 3, "expected end of line, found \"\\t\\t\"".into()),
+                (2, 0, "expected integer, found \"3\\t4\\t\\t\"".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trailing_blank_lines() {
+        assert_eq!(messages("v nl i", "1\n2"), vec![]);
+        assert_eq!(
+            messages("v nl i", "1\n2\n\n\n"),
+            vec![(3, 0, "expected end of input, found \"\\n\\n\"".into())]
+        );
+    }
+
+    #[test]
+    fn test_spec_problems() {
+        assert_eq!(
+            messages("g ['.', '#']", "..#\n.#\n"),
+            vec![(
+                2,
+                0,
+                "expected row of 3 cells like the first row, not 2, found \".#\"".into()
+            )]
+        );
+        assert_eq!(
+            messages("g ['.', '#']", "..#\n.x#"),
+            vec![(2, 1, "expected one of '.', '#', found \"x\"".into())]
+        );
+        let problems = messages("v sp i\nnlnl\ng c", "1 2 3\n");
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].2.starts_with("expected section delimiter"));
+    }
+
+    #[test]
+    fn test_inputs() {
+        for entry in std::fs::read_dir("in").unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                let specs = crate::spec_file::read_spec(&crate::spec_file::spec_path(&path));
+                let input = std::fs::read_to_string(&path).unwrap();
+                assert_eq!(validate(&specs.unwrap(), &input), Ok(vec![]), "{path:?}");
+            }
+        }
+    }
+}